@@ -0,0 +1,242 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! `#[derive(Show)]` for the `cats` crate.
+//!
+//! The derived impl shows a struct or enum much like `{:?}` would, except that each field is shown
+//! with its own `Show` impl, so strings come out without quotes. A field can be left out with
+//! `#[cats(skip)]`, or shown through a `Format` with `#[cats(format = expr)]`.
+//!
+//! # Examples
+//! ```
+//! #[macro_use] extern crate cats;
+//! #[macro_use] extern crate cats_derive;
+//!
+//! #[derive(Show)]
+//! struct Request {
+//!     method: &'static str,
+//!     #[cats(format = cats::HEX)]
+//!     id:     u32,
+//!     #[cats(skip)]
+//!     token:  String,
+//! }
+//!
+//! #[derive(Show)]
+//! enum Shape {
+//!     Circle(u32),
+//!     Empty,
+//! }
+//!
+//! # fn main() {
+//! let req = Request { method: "GET", id: 255, token: String::from("hunter2") };
+//!
+//! assert_eq!(scat!(req), "Request { method: GET, id: ff }");
+//! assert_eq!(scat!(Shape::Circle(3), ' ', Shape::Empty), "Circle(3) Empty");
+//! # }
+//! ```
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use] extern crate quote;
+extern crate syn;
+
+use proc_macro2::{Ident, Span, TokenStream};
+use syn::{Data, DeriveInput, Expr, Fields, Index};
+
+// One piece of a derived impl's output: either fixed text, or a field shown by its own impl or
+// through a `Format`.
+enum Part {
+    Lit(String),
+    Field(Ident, Option<Expr>),
+}
+
+struct FieldAttrs {
+    skip:   bool,
+    format: Option<Expr>,
+}
+
+fn field_attrs(attrs: &[syn::Attribute]) -> syn::Result<FieldAttrs> {
+    let mut out = FieldAttrs { skip: false, format: None };
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("cats")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                out.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("format") {
+                out.format = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip` or `format = ...`"))
+            }
+        })?;
+    }
+
+    Ok(out)
+}
+
+fn push_lit(parts: &mut Vec<Part>, s: &str) {
+    if let Some(&mut Part::Lit(ref mut last)) = parts.last_mut() {
+        last.push_str(s);
+        return;
+    }
+
+    parts.push(Part::Lit(s.to_string()));
+}
+
+// A match arm: the pattern which binds the shown fields of a struct or variant, and the parts
+// that show it.
+struct Arm {
+    pat:   TokenStream,
+    parts: Vec<Part>,
+}
+
+fn arm(path: TokenStream, name: &Ident, fields: &Fields) -> syn::Result<Arm> {
+    let mut parts = vec![Part::Lit(name.to_string())];
+    let mut binds = Vec::new();
+    let mut shown = 0;
+
+    let (open, close) = match *fields {
+        Fields::Named(_)   => (" { ", " }"),
+        Fields::Unnamed(_) => ("(", ")"),
+        Fields::Unit       => return Ok(Arm { pat: path, parts }),
+    };
+
+    for (i, field) in fields.iter().enumerate() {
+        let attrs = field_attrs(&field.attrs)?;
+
+        if attrs.skip {
+            continue;
+        }
+
+        let bind   = Ident::new(&format!("field{}", i), Span::mixed_site());
+        let member = match field.ident {
+            Some(ref ident) => quote!(#ident),
+            None            => { let i = Index::from(i); quote!(#i) },
+        };
+
+        push_lit(&mut parts, if shown == 0 { open } else { ", " });
+
+        if let Some(ref ident) = field.ident {
+            push_lit(&mut parts, &format!("{}: ", ident));
+        }
+
+        parts.push(Part::Field(bind.clone(), attrs.format));
+        binds.push(quote!(#member: ref #bind));
+        shown += 1;
+    }
+
+    if shown > 0 {
+        push_lit(&mut parts, close);
+    }
+
+    // Braces work for tuple structs and variants too, with the positions as field names.
+    Ok(Arm { pat: quote!(#path { #(#binds,)* .. }), parts })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+
+    let arms = match input.data {
+        Data::Struct(ref data) => vec![arm(quote!(#name), name, &data.fields)?],
+        Data::Enum(ref data)   => data.variants.iter().map(|v| {
+            let variant = &v.ident;
+
+            arm(quote!(#name::#variant), variant, &v.fields)
+        }).collect::<syn::Result<_>>()?,
+        Data::Union(_)         => {
+            return Err(syn::Error::new(name.span(), "#[derive(Show)] doesn't support unions"));
+        },
+    };
+
+    let w = Ident::new("w", Span::mixed_site());
+    let n = Ident::new("n", Span::mixed_site());
+
+    let mut pats   = Vec::new();
+    let mut lens   = Vec::new();
+    let mut writes = Vec::new();
+
+    for arm in arms {
+        let len = arm.parts.iter().map(|part| match *part {
+            Part::Lit(ref s)                     => { let len = s.len(); quote!(#len) },
+            Part::Field(ref bind, None)          => quote!(::cats::Show::len(#bind)),
+            Part::Field(ref bind, Some(ref fmt)) => quote!(::cats::Format::len(&(#fmt), #bind)),
+        });
+
+        let write = arm.parts.iter().map(|part| match *part {
+            Part::Lit(ref s)                     => quote!(::cats::Show::write(#s, #w)?),
+            Part::Field(ref bind, None)          => quote!(::cats::Show::write(#bind, #w)?),
+            Part::Field(ref bind, Some(ref fmt)) => {
+                quote!(::cats::Format::write(&(#fmt), #bind, #w)?)
+            },
+        });
+
+        pats.push(arm.pat);
+        lens.push(quote!(0 #(+ #len)*));
+        writes.push(quote!({
+            let mut #n = 0;
+            #( #n += #write; )*
+            ::std::result::Result::Ok(#n)
+        }));
+    }
+
+    // Like the std derives, require every type parameter to implement the trait.
+    let mut generics = input.generics.clone();
+
+    for param in input.generics.type_params() {
+        let ident = &param.ident;
+
+        generics.make_where_clause().predicates.push(syn::parse_quote!(#ident: ::cats::Show));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::cats::Show for #name #ty_generics #where_clause {
+            fn len(&self) -> usize {
+                match *self {
+                    #( #pats => #lens, )*
+                }
+            }
+
+            fn write<W: ::std::io::Write>(&self, #w: &mut W) -> ::std::io::Result<usize> {
+                match *self {
+                    #( #pats => #writes, )*
+                }
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(Show, attributes(cats))]
+pub fn derive_show(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let expanded = syn::parse(input).and_then(expand);
+
+    match expanded {
+        Ok(tokens) => tokens.into(),
+        Err(e)     => compile_error(e).into(),
+    }
+}
+
+// `syn::Error::to_compile_error` refers to `::core`, which 2015 edition crates can't see.
+fn compile_error(e: syn::Error) -> TokenStream {
+    e.into_iter().map(|e| {
+        let msg = e.to_string();
+
+        quote_spanned!(e.span()=> compile_error!(#msg))
+    }).collect()
+}
@@ -0,0 +1,138 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+// Parsing for the arguments of the cat macros, `scat!`, `cat!`, `fcat!` and the rest. Each argument
+// is one of
+//
+//     obj | fmt ; obj | (fmt ; obj) | literal
+//
+// separated by commas, with an optional trailing comma. This is checked here rather than left to
+// the `macro_rules!` that write the cat, which can only say that no rule matched, and which read a
+// parenthesized pair followed by anything but a comma, like `(fmt ; obj).len()`, as
+// `fmt ; obj.len()`.
+
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::token::{Comma, Paren, Semi};
+use syn::{Expr, ExprLit, ExprUnary, Lit, UnOp};
+
+pub enum Arg {
+    // A literal is passed along as it is, so the writing end can tell it's a literal.
+    Lit(Expr),
+    Show(Expr),
+    Format(Expr, Expr),
+}
+
+pub struct CatArgs(pub Vec<Arg>);
+
+impl Parse for CatArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut args = Vec::new();
+
+        while !input.is_empty() {
+            if input.peek(Comma) {
+                return Err(input.error("expected a cat argument before this `,`"));
+            }
+
+            args.push(parse_arg(input)?);
+
+            if input.is_empty() {
+                break;
+            }
+
+            if input.peek(Semi) {
+                return Err(input.error("a format takes a single object, use `Compose` to apply \
+                                        more than one format"));
+            }
+
+            if !input.peek(Comma) {
+                return Err(input.error("expected `,` between cat arguments"));
+            }
+
+            input.parse::<Comma>()?;
+        }
+
+        Ok(CatArgs(args))
+    }
+}
+
+fn parse_arg(input: ParseStream) -> syn::Result<Arg> {
+    if !is_paren_pair(input) {
+        return parse_pair(input);
+    }
+
+    let content;
+
+    syn::parenthesized!(content in input);
+
+    let arg = parse_pair(&content)?;
+
+    if !content.is_empty() {
+        return Err(content.error("expected `)` after the object"));
+    }
+
+    if !input.is_empty() && !input.peek(Comma) {
+        return Err(input.error("expected `,` after a parenthesized `fmt ; obj`"));
+    }
+
+    Ok(arg)
+}
+
+// Is the next argument a parenthesized `fmt ; obj`, rather than an expression in parentheses?
+fn is_paren_pair(input: ParseStream) -> bool {
+    let pair = |input: ParseStream| -> syn::Result<bool> {
+        let content;
+
+        syn::parenthesized!(content in input);
+
+        Ok(content.parse::<Expr>().is_ok() && content.peek(Semi))
+    };
+
+    input.peek(Paren) && pair(&input.fork()).unwrap_or(false)
+}
+
+fn parse_pair(input: ParseStream) -> syn::Result<Arg> {
+    let first: Expr = input.parse()?;
+
+    if !input.peek(Semi) {
+        return Ok(if is_literal(&first) { Arg::Lit(first) } else { Arg::Show(first) });
+    }
+
+    let semi: Semi = input.parse()?;
+
+    if is_literal(&first) {
+        return Err(syn::Error::new(first.span(), "a literal can't be a format, use `,` to \
+                                                  separate cat arguments"));
+    }
+
+    if input.is_empty() || input.peek(Comma) {
+        return Err(syn::Error::new(semi.span, "expected an object after `;`"));
+    }
+
+    Ok(Arg::Format(first, input.parse()?))
+}
+
+// Matches what `$lit:literal` does in `macro_rules!`, including a negated number.
+fn is_literal(e: &Expr) -> bool {
+    match *e {
+        Expr::Lit(_) => true,
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), ref expr, .. }) => matches!(**expr,
+            Expr::Lit(ExprLit { lit: Lit::Int(_), .. }) |
+            Expr::Lit(ExprLit { lit: Lit::Float(_), .. })),
+        _ => false,
+    }
+}
@@ -0,0 +1,443 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Procedural macros used by `cats`. They're re-exported from there, and expect to be handed the
+//! path to `cats` as their first argument, so they shouldn't be used directly.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+#[macro_use] extern crate quote;
+extern crate syn;
+
+mod args;
+mod spec;
+
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use syn::parse::{Parse, ParseStream};
+use syn::{Expr, LitStr};
+use syn::spanned::Spanned;
+use syn::token::{Comma, Eq};
+
+use args::{Arg, CatArgs};
+use spec::{Align, ArgRef, Piece, Spec};
+
+struct CatfInput {
+    fmt:        LitStr,
+    positional: Vec<Expr>,
+    named:      Vec<(Ident, Expr)>,
+}
+
+impl Parse for CatfInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let fmt            = input.parse()?;
+        let mut positional = Vec::new();
+        let mut named      = Vec::<(Ident, Expr)>::new();
+
+        while !input.is_empty() {
+            input.parse::<Comma>()?;
+
+            if input.is_empty() {
+                break;
+            }
+
+            if input.peek(syn::Ident) && input.peek2(Eq) {
+                let name: Ident = input.parse()?;
+                input.parse::<Eq>()?;
+
+                if named.iter().any(|(n, _)| *n == name) {
+                    return Err(syn::Error::new(name.span(), "duplicate argument name"));
+                }
+
+                named.push((name, input.parse()?));
+            } else if named.is_empty() {
+                positional.push(input.parse()?);
+            } else {
+                return Err(input.error("positional arguments can't follow named arguments"));
+            }
+        }
+
+        Ok(CatfInput { fmt, positional, named })
+    }
+}
+
+// Split off the leading `$crate` path that `cats` passes in front of the user's arguments.
+fn split_krate(input: TokenStream) -> (TokenStream, TokenStream) {
+    let mut tokens = input.into_iter();
+    let mut krate  = TokenStream::new();
+
+    for tt in tokens.by_ref() {
+        match tt {
+            TokenTree::Punct(ref p) if p.as_char() == ',' => break,
+            tt                                            => krate.extend(Some(tt)),
+        }
+    }
+
+    (krate, tokens.collect())
+}
+
+const DECIMAL: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+const LOWER:   &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd',
+                           'e', 'f'];
+const OCTAL:   &[char] = &['0', '1', '2', '3', '4', '5', '6', '7'];
+const BINARY:  &[char] = &['0', '1'];
+
+// The `FormattedInt` a spec asks for, if it needs one.
+fn int_format(krate: &TokenStream, spec: &Spec) -> Option<TokenStream> {
+    if !spec.is_integer() {
+        return None;
+    }
+
+    let (digits, prefix) = match spec.ty {
+        Some('x') | Some('X') => (LOWER, "0x"),
+        Some('o')             => (OCTAL, "0o"),
+        Some('b')             => (BINARY, "0b"),
+        _                     => (DECIMAL, ""),
+    };
+
+    let prefix  = if spec.alternate { prefix } else { "" };
+    let min_len = if spec.zero { spec.width.unwrap_or(0) } else { 0 };
+    let sign    = Ident::new(if spec.plus { "Plus" } else { "Empty" }, Span::call_site());
+    let upper   = spec.ty == Some('X');
+
+    Some(quote! {
+        #krate::FormattedInt {
+            prefix:  #prefix,
+            digits:  &[#(#digits),*],
+            min_len: #min_len,
+            sign:    #krate::SignPolicy::#sign,
+
+            uppercase:  #upper,
+            full_width: true,
+            .. #krate::HEX
+        }
+    })
+}
+
+// The `Pad` a spec asks for, if it needs one, for the argument bound to `value`.
+fn pad_format(krate: &TokenStream, spec: &Spec, value: &Ident) -> Option<TokenStream> {
+    let width = match spec.width {
+        Some(width) if !spec.zero => width,
+        _                         => return None,
+    };
+
+    let align = |align: &str| {
+        let align = Ident::new(align, Span::call_site());
+
+        quote!(#krate::Align::#align)
+    };
+
+    // Like `std::fmt`, numbers are right-aligned by default and everything else is left-aligned.
+    // A spec that formats its argument as an integer says which it is, and otherwise it's up to
+    // the argument's type, which only the compiler knows.
+    let align = match spec.align {
+        Some(Align::Left)         => align("Left"),
+        Some(Align::Right)        => align("Right"),
+        Some(Align::Center)       => align("Center"),
+        None if spec.is_integer() => align("Right"),
+        None                      => quote! {{
+            use #krate::{__NumericAlign, __OtherAlign};
+
+            (&#krate::__AlignOf(#value)).__default_align()
+        }},
+    };
+
+    let fill = spec.fill.unwrap_or(' ');
+
+    Some(quote! {
+        #krate::Pad {
+            width:    #width,
+            fill:     #fill,
+            align:    #align,
+            overflow: #krate::Overflow::Expand,
+        }
+    })
+}
+
+fn expand_catf(krate: TokenStream, input: CatfInput) -> syn::Result<TokenStream> {
+    let pieces = spec::parse(&input.fmt.value())
+        .map_err(|msg| syn::Error::new(input.fmt.span(), msg))?;
+
+    let num_positional = input.positional.len();
+
+    let mut exprs: Vec<TokenStream> = input.positional.iter().map(|e| quote!(#e)).collect();
+    let mut spans: Vec<Span>        = input.positional.iter().map(Spanned::span).collect();
+    let mut names: Vec<String>      = Vec::new();
+
+    for (name, e) in &input.named {
+        exprs.push(quote!(#e));
+        spans.push(e.span());
+        names.push(name.to_string());
+    }
+
+    let mut used = vec![false; exprs.len()];
+    let mut next = 0;
+
+    // The arguments to `produce_len_code!` and `produce_write_code!`, plus a check for each use
+    // of an argument that its type can be shown the way the spec asks.
+    let mut segments = Vec::new();
+    let mut checks   = Vec::new();
+
+    for piece in &pieces {
+        let (arg, spec) = match *piece {
+            Piece::Lit(ref s) => {
+                segments.push(quote!(#s));
+                continue;
+            },
+            Piece::Arg(ref arg, ref spec) => (arg, spec),
+        };
+
+        let index = match *arg {
+            ArgRef::Next     => { next += 1; next - 1 },
+            ArgRef::Index(i) => i,
+            ArgRef::Name(ref name) => match names.iter().position(|n| n == name) {
+                Some(i) => num_positional + i,
+                None    => {
+                    // Not one of the named arguments, so capture a variable from the caller's
+                    // scope. Giving it the format string's span makes it resolve there.
+                    let ident = Ident::new(name, input.fmt.span());
+
+                    exprs.push(quote!(#ident));
+                    spans.push(input.fmt.span());
+                    names.push(name.clone());
+                    used.push(false);
+
+                    exprs.len() - 1
+                },
+            },
+        };
+
+        if index >= num_positional && matches!(*arg, ArgRef::Next | ArgRef::Index(_)) {
+            let msg = format!("format string refers to argument {}, but only {} positional \
+                               argument(s) were given", index, num_positional);
+
+            return Err(syn::Error::new(input.fmt.span(), msg));
+        }
+
+        used[index] = true;
+
+        // The bindings carry the argument's span, so that if a check below fails the error points
+        // at the argument instead of at the whole `catf!`. Nothing the caller wrote is in scope
+        // where they're used, so that can't capture anything.
+        let span  = spans[index];
+        let value = arg_ident(index, span);
+        let fmt   = Ident::new("fmt", span);
+
+        let check_format = |int: &TokenStream| quote_spanned! {span=>
+            { let #fmt = #int; #krate::__assert_format(&#fmt, #value) }
+        };
+
+        let (segment, check) = match (int_format(&krate, spec), pad_format(&krate, spec, &value)) {
+            (None, None) => (
+                quote!(*#value),
+                quote_spanned!(span=> #krate::__assert_show(#value)),
+            ),
+            (None, Some(pad)) => (
+                quote!(#pad ; *#value),
+                quote_spanned!(span=> #krate::__assert_show(#value)),
+            ),
+            (Some(int), None) => (
+                quote!(#int ; *#value),
+                check_format(&int),
+            ),
+            (Some(int), Some(pad)) => (
+                quote!(#pad ; #krate::Formatted(#int, #value)),
+                check_format(&int),
+            ),
+        };
+
+        segments.push(segment);
+        checks.push(check);
+    }
+
+    if let Some(i) = used.iter().position(|&u| !u) {
+        let span = if i < num_positional {
+            spans[i]
+        } else {
+            input.named[i - num_positional].0.span()
+        };
+
+        return Err(syn::Error::new(span, "argument never used"));
+    }
+
+    if segments.is_empty() {
+        return Ok(quote!(::std::string::String::new()));
+    }
+
+    let values: Vec<Ident> = (0..exprs.len()).map(|i| arg_ident(i, spans[i])).collect();
+
+    let len    = Ident::new("len", Span::mixed_site());
+    let buffer = Ident::new("buffer", Span::mixed_site());
+
+    Ok(quote! {{
+        // Bind every argument once, so each is evaluated once even though it's used in two passes.
+        match (#(&(#exprs),)*) {
+            (#(#values,)*) => {
+                #( #checks; )*
+
+                let mut #len: usize = 0;
+                let #len = #krate::produce_len_code!(#len, #(#segments),*);
+
+                let mut #buffer = ::std::vec::Vec::new();
+                #krate::__reserve(&mut #buffer, #len);
+
+                #krate::__expect_written(
+                    #krate::produce_write_code!(0, &mut #buffer, #(#segments),*), "catf!");
+
+                match ::std::string::String::from_utf8(#buffer) {
+                    ::std::result::Result::Ok(s) => s,
+                    _ => panic!("catf! macro generated invalid utf-8"),
+                }
+            }
+        }
+    }})
+}
+
+fn arg_ident(index: usize, span: Span) -> Ident {
+    Ident::new(&format!("arg{}", index), span)
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __catf(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (krate, rest) = split_krate(input.into());
+
+    let expanded = syn::parse2(rest).and_then(|input| expand_catf(krate, input));
+
+    match expanded {
+        Ok(tokens) => tokens.into(),
+        Err(e)     => compile_error(e).into(),
+    }
+}
+
+// What `__cat_args!` and `__cat_check!` are handed: the macro to call with the cat once it's been
+// checked, the tokens to call it with ahead of the cat, and the cat's arguments as written.
+struct CatInput {
+    callback: Ident,
+    extra:    TokenStream,
+    args:     TokenStream,
+}
+
+impl Parse for CatInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let callback = input.parse()?;
+        let extra;
+
+        syn::parenthesized!(extra in input);
+
+        Ok(CatInput { callback, extra: extra.parse()?, args: input.parse()? })
+    }
+}
+
+fn expand_cat_args(krate: TokenStream, input: CatInput) -> syn::Result<TokenStream> {
+    let args: CatArgs = syn::parse2(input.args)?;
+
+    let mut exprs  = Vec::new();
+    let mut values = Vec::new();
+    let mut checks = Vec::new();
+    let mut segs   = Vec::new();
+
+    // Like `catf!`'s, the bindings carry the argument's span so that a failed check points at the
+    // argument. They resolve where they're introduced, though, since the tokens passed along ahead
+    // of the cat may well include the caller's own variables. That alone would have the compiler
+    // report the error at the whole macro, so each check also puts the binding in a block of the
+    // caller's.
+    let mut bind = |prefix: &str, e: &Expr| {
+        let ident = Ident::new(&format!("{}{}", prefix, exprs.len()),
+                               Span::mixed_site().located_at(e.span()));
+
+        exprs.push(e.clone());
+        values.push(ident.clone());
+
+        ident
+    };
+
+    for arg in &args.0 {
+        match *arg {
+            Arg::Lit(ref lit) => segs.push(quote!(#lit,)),
+            Arg::Show(ref obj) => {
+                let span = obj.span();
+                let x    = bind("x", obj);
+
+                checks.push(quote_spanned!(span=> #krate::__assert_show({ #x })));
+                segs.push(quote!(*#x,));
+            },
+            Arg::Format(ref fmt, ref obj) => {
+                let span = obj.span();
+                let f    = bind("f", fmt);
+                let x    = bind("x", obj);
+
+                checks.push(quote_spanned!(span=> #krate::__assert_format({ #f }, { #x })));
+                segs.push(quote!(*#f ; *#x,));
+            },
+        }
+    }
+
+    let callback = &input.callback;
+    let extra    = &input.extra;
+
+    // A match, rather than lets, keeps temporaries in the arguments alive until the cat is done.
+    Ok(quote! {
+        match (#(&(#exprs),)*) {
+            (#(#values,)*) => {
+                #( #checks; )*
+
+                #krate::#callback!(#extra #(#segs)*)
+            }
+        }
+    })
+}
+
+// The cat macros that write their arguments in a single pass don't need them bound, so they're
+// handed back as they were once they've been checked.
+fn expand_cat_check(krate: TokenStream, input: CatInput) -> syn::Result<TokenStream> {
+    syn::parse2::<CatArgs>(input.args.clone())?;
+
+    let CatInput { callback, extra, args } = input;
+
+    Ok(quote!(#krate::#callback!(#extra #args)))
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __cat_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (krate, rest) = split_krate(input.into());
+
+    match syn::parse2(rest).and_then(|input| expand_cat_args(krate, input)) {
+        Ok(tokens) => tokens.into(),
+        Err(e)     => compile_error(e).into(),
+    }
+}
+
+#[doc(hidden)]
+#[proc_macro]
+pub fn __cat_check(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let (krate, rest) = split_krate(input.into());
+
+    match syn::parse2(rest).and_then(|input| expand_cat_check(krate, input)) {
+        Ok(tokens) => tokens.into(),
+        Err(e)     => compile_error(e).into(),
+    }
+}
+
+// `syn::Error::to_compile_error` refers to `::core`, which 2015 edition crates can't see.
+fn compile_error(e: syn::Error) -> TokenStream {
+    e.into_iter().map(|e| {
+        let msg = e.to_string();
+
+        quote_spanned!(e.span()=> compile_error!(#msg))
+    }).collect()
+}
@@ -0,0 +1,214 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+// Parsing for the format strings accepted by `catf!`. This is a subset of the `std::fmt` syntax:
+//
+//     {[arg][:[[fill]align][+][#][0][width][type]]}
+//
+// where `arg` is a position or a name, `align` is one of '<', '^' or '>', and `type` is one of 'x',
+// 'X', 'o' or 'b'. Literal braces are written "{{" and "}}".
+
+pub enum Piece {
+    Lit(String),
+    Arg(ArgRef, Spec),
+}
+
+pub enum ArgRef {
+    Next,
+    Index(usize),
+    Name(String),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+pub struct Spec {
+    pub fill:      Option<char>,
+    pub align:     Option<Align>,
+    pub plus:      bool,
+    pub alternate: bool,
+    pub zero:      bool,
+    pub width:     Option<usize>,
+    pub ty:        Option<char>,
+}
+
+impl Spec {
+    // Does this spec need the argument to be formatted as an integer?
+    pub fn is_integer(&self) -> bool {
+        self.ty.is_some() || self.plus || self.alternate || self.zero
+    }
+}
+
+pub fn parse(fmt: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut lit    = String::new();
+    let mut chars  = fmt.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => { chars.next(); lit.push('{'); },
+            '}' if chars.peek() == Some(&'}') => { chars.next(); lit.push('}'); },
+
+            '}' => return Err("unmatched `}` in format string, use `}}` for a literal".into()),
+
+            '{' => {
+                let mut inner = String::new();
+
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c)   => inner.push(c),
+                        None      => return Err("unterminated `{` in format string".into()),
+                    }
+                }
+
+                if !lit.is_empty() {
+                    pieces.push(Piece::Lit(lit.clone()));
+                    lit.clear();
+                }
+
+                let (arg, spec) = match inner.find(':') {
+                    Some(i) => (&inner[..i], &inner[i + 1..]),
+                    None    => (&inner[..], ""),
+                };
+
+                pieces.push(Piece::Arg(parse_arg(arg.trim())?, parse_spec(spec)?));
+            },
+
+            c => lit.push(c),
+        }
+    }
+
+    if !lit.is_empty() {
+        pieces.push(Piece::Lit(lit));
+    }
+
+    Ok(pieces)
+}
+
+fn parse_arg(arg: &str) -> Result<ArgRef, String> {
+    if arg.is_empty() {
+        return Ok(ArgRef::Next);
+    }
+
+    if let Ok(i) = arg.parse() {
+        return Ok(ArgRef::Index(i));
+    }
+
+    let mut chars = arg.chars();
+    let valid     = match chars.next() {
+        Some(c) => c.is_alphabetic() || c == '_',
+        None    => false,
+    };
+
+    let valid = valid && chars.all(|c| c.is_alphanumeric() || c == '_');
+
+    if valid {
+        Ok(ArgRef::Name(arg.into()))
+    } else {
+        Err(format!("invalid argument name `{}` in format string", arg))
+    }
+}
+
+fn to_align(c: char) -> Option<Align> {
+    match c {
+        '<' => Some(Align::Left),
+        '>' => Some(Align::Right),
+        '^' => Some(Align::Center),
+        _   => None,
+    }
+}
+
+fn parse_spec(spec: &str) -> Result<Spec, String> {
+    let chars: Vec<char> = spec.chars().collect();
+    let mut i            = 0;
+
+    let mut parsed = Spec {
+        fill:      None,
+        align:     None,
+        plus:      false,
+        alternate: false,
+        zero:      false,
+        width:     None,
+        ty:        None,
+    };
+
+    if chars.len() >= 2 && to_align(chars[1]).is_some() {
+        parsed.fill  = Some(chars[0]);
+        parsed.align = to_align(chars[1]);
+        i = 2;
+    } else if !chars.is_empty() && to_align(chars[0]).is_some() {
+        parsed.align = to_align(chars[0]);
+        i = 1;
+    }
+
+    if i < chars.len() && chars[i] == '+' {
+        parsed.plus = true;
+        i += 1;
+    }
+
+    if i < chars.len() && chars[i] == '#' {
+        parsed.alternate = true;
+        i += 1;
+    }
+
+    if i < chars.len() && chars[i] == '0' {
+        parsed.zero = true;
+        i += 1;
+    }
+
+    let start = i;
+
+    while i < chars.len() && chars[i].is_ascii_digit() {
+        i += 1;
+    }
+
+    if i > start {
+        let digits: String = chars[start..i].iter().cloned().collect();
+
+        let width = digits.parse().map_err(|_| format!("width `{}` is too large", digits))?;
+
+        parsed.width = Some(width);
+    }
+
+    if i < chars.len() {
+        match chars[i] {
+            'x' | 'X' | 'o' | 'b' => { parsed.ty = Some(chars[i]); i += 1; },
+            '.' => return Err("precision is not supported by catf!".into()),
+            '?' => return Err("debug formatting is not supported by catf!".into()),
+            _   => {},
+        }
+    }
+
+    if i < chars.len() {
+        return Err(format!("invalid format spec `{}`", spec));
+    }
+
+    if parsed.zero && (parsed.fill.is_some() || parsed.align.is_some()) {
+        return Err("the `0` flag can't be combined with a fill or alignment".into());
+    }
+
+    if parsed.zero && parsed.width.is_none() {
+        return Err("the `0` flag needs a width".into());
+    }
+
+    Ok(parsed)
+}
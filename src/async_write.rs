@@ -0,0 +1,83 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use __tokio::io::AsyncWrite;
+
+/// The future returned by `afcat!` and the other async cats. The cat has already been rendered
+/// into a buffer that this owns, and polling it writes the buffer to `W` without blocking.
+///
+/// Requires the `tokio` feature.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WriteCat<W> {
+    w:     W,
+    buf:   Vec<u8>,
+    pos:   usize,
+    // Why the cat couldn't be rendered, if it couldn't. It's the future's result.
+    error: Option<io::Error>,
+}
+
+impl<W> WriteCat<W>
+where W: AsyncWrite + Unpin {
+    #[doc(hidden)]
+    pub fn new(w: W, buf: io::Result<Vec<u8>>) -> WriteCat<W> {
+        match buf {
+            Ok(buf)    => WriteCat { w, buf, pos: 0, error: None },
+            Err(error) => WriteCat { w, buf: Vec::new(), pos: 0, error: Some(error) },
+        }
+    }
+}
+
+impl<W> Future for WriteCat<W>
+where W: AsyncWrite + Unpin {
+    type Output = io::Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if let Some(error) = this.error.take() {
+            return Poll::Ready(Err(error));
+        }
+
+        while this.pos < this.buf.len() {
+            match Pin::new(&mut this.w).poll_write(cx, &this.buf[this.pos..]) {
+                Poll::Ready(Ok(0))  => return Poll::Ready(Err(io::ErrorKind::WriteZero.into())),
+                Poll::Ready(Ok(n))  => this.pos += n,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending       => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+// Lets the macros reborrow `$file` if it's already a `&mut W` and borrow it otherwise, which is
+// what `io::Write::by_ref` does for `fcat!`. Method syntax is what makes the difference.
+#[doc(hidden)]
+pub trait AsyncByRef: AsyncWrite + Unpin {
+    fn async_by_ref(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl<W> AsyncByRef for W
+where W: AsyncWrite + Unpin + ?Sized {}
@@ -0,0 +1,112 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::fmt;
+use std::io::{self, Write};
+
+use traits::{Show, SizeHint, measure, write_counted};
+
+/// Show anything that implements `std::fmt::Display`. The length comes from a counting pass
+/// through `Display`, so the value is formatted twice per cat.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Disp;
+/// # use std::net::Ipv4Addr;
+/// # fn main() {
+/// let addr = Ipv4Addr::new(127, 0, 0, 1);
+///
+/// assert_eq!(scat!("listening on ", Disp(addr), ':', 8080), "listening on 127.0.0.1:8080");
+/// # }
+/// ```
+pub struct Disp<T>(pub T);
+
+impl<T> Show for Disp<T>
+where T: fmt::Display {
+    fn len(&self) -> usize {
+        measure(|w| write!(w, "{}", self.0))
+    }
+
+    // Finding the length takes a whole pass through `fmt`, so buffers just grow to fit instead.
+    fn size_hint(&self) -> SizeHint { SizeHint::AtLeast(0) }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| write!(w, "{}", self.0))
+    }
+}
+
+/// Show anything that implements `std::fmt::Debug`, the same way `Disp` does for `Display`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Dbg;
+/// # fn main() {
+/// #[derive(Debug)]
+/// struct Config { lives: u8, name: &'static str }
+///
+/// let config = Config { lives: 9, name: "Tom" };
+///
+/// assert_eq!(scat!("state: ", Dbg(&config)), r#"state: Config { lives: 9, name: "Tom" }"#);
+/// # }
+/// ```
+pub struct Dbg<T>(pub T);
+
+impl<T> Show for Dbg<T>
+where T: fmt::Debug {
+    fn len(&self) -> usize {
+        measure(|w| write!(w, "{:?}", self.0))
+    }
+
+    // Finding the length takes a whole pass through `fmt`, so buffers just grow to fit instead.
+    fn size_hint(&self) -> SizeHint { SizeHint::AtLeast(0) }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| write!(w, "{:?}", self.0))
+    }
+}
+
+/// Format arguments are shown as-is, so `format_args!` can be used for anything cats has no
+/// `Format` for. Like `Disp`, the length takes a counting pass.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let v = vec![1, 2];
+///
+/// assert_eq!(scat!("x=", format_args!("{:?}", v)), "x=[1, 2]");
+/// # }
+/// ```
+impl<'a> Show for fmt::Arguments<'a> {
+    fn len(&self) -> usize { Show::len(&Disp(self)) }
+
+    fn size_hint(&self) -> SizeHint {
+        match self.as_str() {
+            Some(s) => SizeHint::Exact(s.len()),
+            None    => SizeHint::AtLeast(0),
+        }
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        match self.as_str() {
+            Some(s) => Show::write(s, w),
+            None    => Show::write(&Disp(self), w),
+        }
+    }
+}
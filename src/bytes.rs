@@ -0,0 +1,265 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::io;
+
+use traits::{Show, Format};
+
+const LOWER_HEX: &[u8; 16] = b"0123456789abcdef";
+const UPPER_HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+// Write the two hex digits of `b`.
+fn push_hex<W: io::Write>(b: u8, uppercase: bool, w: &mut W) -> io::Result<usize> {
+    let table = if uppercase { UPPER_HEX } else { LOWER_HEX };
+
+    w.write_all(&[table[(b >> 4) as usize], table[(b & 0xf) as usize]]).map(|()| 2)
+}
+
+/// Format 16 bytes as a UUID in the canonical hyphenated form,
+/// "xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx".
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Uuid;
+/// # fn main() {
+/// let id = [0x12, 0x3e, 0x45, 0x67, 0xe8, 0x9b, 0x12, 0xd3,
+///           0xa4, 0x56, 0x42, 0x66, 0x14, 0x17, 0x40, 0x00];
+///
+/// assert_eq!(scat!(Uuid { uppercase: false }; id), "123e4567-e89b-12d3-a456-426614174000");
+/// # }
+/// ```
+pub struct Uuid {
+    pub uppercase: bool,
+}
+
+impl Format<[u8; 16]> for Uuid {
+    fn len(&self, _: &[u8; 16]) -> usize { 36 }
+
+    fn write<W: io::Write>(&self, bytes: &[u8; 16], w: &mut W) -> io::Result<usize> {
+        let mut written = 0;
+
+        for (i, b) in bytes.iter().enumerate() {
+            if i == 4 || i == 6 || i == 8 || i == 10 {
+                w.write_all(b"-")?;
+                written += 1;
+            }
+
+            written += push_hex(*b, self.uppercase, w)?;
+        }
+
+        Ok(written)
+    }
+}
+
+/// Format a 6-byte MAC address as hex pairs joined by `sep`, as in "aa:bb:cc:dd:ee:ff".
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::MacAddr;
+/// # fn main() {
+/// let mac = [0x00, 0x1b, 0x44, 0x11, 0x3a, 0xb7];
+///
+/// assert_eq!(scat!(MacAddr { sep: ':', uppercase: false }; mac), "00:1b:44:11:3a:b7");
+/// assert_eq!(scat!(MacAddr { sep: '-', uppercase: true }; mac),  "00-1B-44-11-3A-B7");
+/// # }
+/// ```
+pub struct MacAddr {
+    pub sep:       char,
+    pub uppercase: bool,
+}
+
+impl Format<[u8; 6]> for MacAddr {
+    fn len(&self, _: &[u8; 6]) -> usize { 12 + 5 * self.sep.len_utf8() }
+
+    fn write<W: io::Write>(&self, bytes: &[u8; 6], w: &mut W) -> io::Result<usize> {
+        let mut written = 0;
+
+        for (i, b) in bytes.iter().enumerate() {
+            if i > 0 {
+                written += Show::write(&self.sep, w)?;
+            }
+
+            written += push_hex(*b, self.uppercase, w)?;
+        }
+
+        Ok(written)
+    }
+}
+
+/// Format an RGB triple as a CSS-style hex color, as in "#1a2b3c".
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::RgbHex;
+/// # fn main() {
+/// assert_eq!(scat!(RgbHex; (0x1a, 0x2b, 0x3c)), "#1a2b3c");
+/// assert_eq!(scat!(RgbHex; [0xff, 0x00, 0x80]), "#ff0080");
+/// # }
+/// ```
+pub struct RgbHex;
+
+impl Format<[u8; 3]> for RgbHex {
+    fn len(&self, _: &[u8; 3]) -> usize { 7 }
+
+    fn write<W: io::Write>(&self, rgb: &[u8; 3], w: &mut W) -> io::Result<usize> {
+        let mut written = Show::write(&'#', w)?;
+
+        for b in rgb.iter() {
+            written += push_hex(*b, false, w)?;
+        }
+
+        Ok(written)
+    }
+}
+
+impl Format<(u8, u8, u8)> for RgbHex {
+    fn len(&self, _: &(u8, u8, u8)) -> usize { 7 }
+    fn write<W: io::Write>(&self, rgb: &(u8, u8, u8), w: &mut W) -> io::Result<usize> {
+        Format::write(self, &[rgb.0, rgb.1, rgb.2], w)
+    }
+}
+
+/// Format a byte slice as a run of hex pairs with no separators, as in "deadbeef".
+///
+/// Long slices are encoded 16 bytes at a time with SSE2 on x86_64, into a stack buffer that's
+/// written out in large pieces, so dumping big buffers costs little more than copying them.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::HexBytes;
+/// # fn main() {
+/// let packet = [0xde, 0xad, 0xbe, 0xef];
+///
+/// assert_eq!(scat!("payload=", HexBytes { uppercase: false }; packet), "payload=deadbeef");
+/// assert_eq!(scat!(HexBytes { uppercase: true }; &packet[2..]), "BEEF");
+/// # }
+/// ```
+pub struct HexBytes {
+    pub uppercase: bool,
+}
+
+// How many bytes are encoded per write.
+const HEX_CHUNK: usize = 256;
+
+impl<T> Format<T> for HexBytes
+where T: AsRef<[u8]> {
+    fn len(&self, t: &T) -> usize { 2 * t.as_ref().len() }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let mut buf = [0; 2 * HEX_CHUNK];
+
+        for chunk in t.as_ref().chunks(HEX_CHUNK) {
+            let out = &mut buf[..2 * chunk.len()];
+
+            encode_hex(chunk, self.uppercase, out);
+            w.write_all(out)?;
+        }
+
+        Ok(self.len(t))
+    }
+}
+
+// Encode `bytes` into `out`, which is exactly twice as long.
+fn encode_hex(bytes: &[u8], uppercase: bool, out: &mut [u8]) {
+    #[cfg(target_arch = "x86_64")]
+    let done = sse2::encode_hex(bytes, uppercase, out);
+
+    #[cfg(not(target_arch = "x86_64"))]
+    let done = 0;
+
+    let table = if uppercase { UPPER_HEX } else { LOWER_HEX };
+
+    for (b, pair) in bytes[done..].iter().zip(out[2 * done..].chunks_mut(2)) {
+        pair[0] = table[(b >> 4) as usize];
+        pair[1] = table[(b & 0xf) as usize];
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+    use std::arch::x86_64::*;
+
+    // Encode as many whole 16-byte blocks of `bytes` as there are, returning how many bytes that
+    // was. SSE2 is part of x86_64, so it's always there.
+    pub fn encode_hex(bytes: &[u8], uppercase: bool, out: &mut [u8]) -> usize {
+        let blocks = bytes.len() / 16;
+        let letter = if uppercase { b'A' } else { b'a' };
+
+        for i in 0..blocks {
+            let src = &bytes[16 * i..16 * i + 16];
+            let dst = &mut out[32 * i..32 * i + 32];
+
+            // Split each byte into its high and low nibble, turn every nibble into its digit by
+            // adding '0', plus the gap up to the letters for nibbles over 9, then interleave the
+            // high and low digits so each byte's pair comes out in order.
+            unsafe {
+                let v    = _mm_loadu_si128(src.as_ptr() as *const __m128i);
+                let mask = _mm_set1_epi8(0x0f);
+                let hi   = _mm_and_si128(_mm_srli_epi16(v, 4), mask);
+                let lo   = _mm_and_si128(v, mask);
+
+                let gap = _mm_set1_epi8((letter - b'0' - 10) as i8);
+                let hi  = digits(hi, gap);
+                let lo  = digits(lo, gap);
+
+                _mm_storeu_si128(dst.as_mut_ptr() as *mut __m128i, _mm_unpacklo_epi8(hi, lo));
+                _mm_storeu_si128(dst[16..].as_mut_ptr() as *mut __m128i, _mm_unpackhi_epi8(hi, lo));
+            }
+        }
+
+        16 * blocks
+    }
+
+    // Turn each nibble in `n` into its digit, where `gap` is how far the letters are past '9' + 1.
+    unsafe fn digits(n: __m128i, gap: __m128i) -> __m128i {
+        let zero    = _mm_set1_epi8(b'0' as i8);
+        let letters = _mm_and_si128(_mm_cmpgt_epi8(n, _mm_set1_epi8(9)), gap);
+
+        _mm_add_epi8(_mm_add_epi8(n, zero), letters)
+    }
+}
+
+/// Write a byte slice exactly as it is.
+///
+/// Unlike everything else in this crate, the bytes needn't be valid utf-8. That makes `Raw` for
+/// binary output with `bcat!`, `vcat!` or `fcat!`. In a `scat!` it will panic if the bytes aren't
+/// utf-8, and it must never be passed to `scat_unchecked!` unless they are.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Raw;
+/// # fn main() {
+/// let frame = bcat!(Raw(&[0x02]), "LEN=", 2, Raw(&[0x00, 0xff]));
+///
+/// assert_eq!(frame, b"\x02LEN=2\x00\xff");
+/// # }
+/// ```
+pub struct Raw<'x>(pub &'x [u8]);
+
+impl<'x> Show for Raw<'x> {
+    fn len(&self) -> usize { self.0.len() }
+    fn stored_bytes(&self) -> Option<&[u8]> { Some(self.0) }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        w.write_all(self.0).map(|()| self.0.len())
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cell::OnceCell;
+use std::io;
+
+use traits::{Show, Format, Formatted, SizeHint, measure};
+
+/// Apply two formats to the same value, one after the other.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Then, Rep};
+/// # fn main() {
+/// let s = scat!(Then(Rep(2), Rep(1)); 'a');
+///
+/// assert_eq!(s, "aaa");
+/// # }
+/// ```
+pub struct Then<A, B>(pub A, pub B);
+
+impl<T, A, B> Format<T> for Then<A, B>
+where A: Format<T>, B: Format<T> {
+    fn len(&self, t: &T) -> usize { self.0.len(t) + self.1.len(t) }
+    fn size_hint(&self, t: &T) -> SizeHint { self.0.size_hint(t) + self.1.size_hint(t) }
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        Ok(self.0.write(t, w)? + self.1.write(t, w)?)
+    }
+}
+
+/// Format a value with `inner`, then format the result of that with `outer`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Compose, Pad, Align, Overflow, HEX};
+/// # fn main() {
+/// let pad = Pad { width: 6, fill: ' ', align: Align::Right, overflow: Overflow::Expand };
+/// let s = scat!('[', Compose(pad, HEX); 0xbeefu32, ']');
+///
+/// assert_eq!(s, "[  beef]");
+/// # }
+/// ```
+pub struct Compose<O, I>(pub O, pub I);
+
+impl<T, O, I> Format<T> for Compose<O, I>
+where I: Format<T>, O: for<'x> Format<Formatted<'x, &'x I, T>> {
+    fn len(&self, t: &T) -> usize { self.0.len(&Formatted(&self.1, t)) }
+    fn size_hint(&self, t: &T) -> SizeHint { self.0.size_hint(&Formatted(&self.1, t)) }
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        self.0.write(&Formatted(&self.1, t), w)
+    }
+}
+
+/// Transform a value with a function before formatting it. The function runs once for `len` and
+/// once for `write`, so it should be cheap and free of side effects.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Map, Rep};
+/// # fn main() {
+/// let s = scat!(Map(|s: &&str| s.len(), Rep(2)); "four");
+///
+/// assert_eq!(s, "44");
+/// # }
+/// ```
+pub struct Map<F, M>(pub F, pub M);
+
+impl<T, U, F, M> Format<T> for Map<F, M>
+where F: Fn(&T) -> U, M: Format<U> {
+    fn len(&self, t: &T) -> usize { self.1.len(&(self.0)(t)) }
+    fn size_hint(&self, t: &T) -> SizeHint { self.1.size_hint(&(self.0)(t)) }
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        self.1.write(&(self.0)(t), w)
+    }
+}
+
+/// A one-off format built from a pair of closures: one that works out the length of a value and
+/// one that writes it. The two must agree, just as `Format::len` and `Format::write` must.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::FnFormat;
+/// # use std::io::Write;
+/// # fn main() {
+/// let yes_no = FnFormat::new(
+///     |b: &bool| if *b { 3 } else { 2 },
+///     |b: &bool, w: &mut dyn Write| {
+///         w.write_all(if *b { b"yes" } else { b"no" })?;
+///         Ok(if *b { 3 } else { 2 })
+///     });
+///
+/// assert_eq!(scat!("purrs: ", yes_no; true, ", bites: ", yes_no; false), "purrs: yes, bites: no");
+/// # }
+/// ```
+pub struct FnFormat<L, F> {
+    len:   L,
+    write: F,
+}
+
+impl<L, F> FnFormat<L, F> {
+    pub fn new<T>(len: L, write: F) -> FnFormat<L, F>
+    where L: Fn(&T) -> usize, F: Fn(&T, &mut dyn io::Write) -> io::Result<usize> {
+        FnFormat { len, write }
+    }
+}
+
+impl<T, L, F> Format<T> for FnFormat<L, F>
+where L: Fn(&T) -> usize, F: Fn(&T, &mut dyn io::Write) -> io::Result<usize> {
+    fn len(&self, t: &T) -> usize { (self.len)(t) }
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        (self.write)(t, w)
+    }
+}
+
+/// Turn a closure that writes something into a `Show`. The length is found by running the closure
+/// against a writer that only counts bytes, so the closure runs once for `len` and again for
+/// `write`, and should produce the same output both times.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{FnShow, Show};
+/// # use std::io::Write;
+/// # fn main() {
+/// let lives = [3u32, 4, 2];
+/// let sum   = FnShow(|mut w: &mut dyn Write| lives.iter().sum::<u32>().write(&mut w));
+///
+/// assert_eq!(scat!("sum=", sum), "sum=9");
+/// # }
+/// ```
+pub struct FnShow<F>(pub F);
+
+impl<F> Show for FnShow<F>
+where F: Fn(&mut dyn io::Write) -> io::Result<usize> {
+    fn len(&self) -> usize {
+        measure(|w| (self.0)(w))
+    }
+
+    fn size_hint(&self) -> SizeHint { SizeHint::AtLeast(0) }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        (self.0)(w)
+    }
+}
+
+/// Put off producing a value until it's first shown. The closure runs at most once, during the
+/// length pass, and the value it returns is kept for the write pass. A cat that never runs, such as
+/// a disabled log line, never calls the closure at all.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Lazy;
+/// # use std::cell::Cell;
+/// # fn main() {
+/// let calls = Cell::new(0);
+/// let total = Lazy::new(|| { calls.set(calls.get() + 1); (1..=100u32).sum::<u32>() });
+///
+/// assert_eq!(calls.get(), 0);
+/// assert_eq!(scat!("total=", total), "total=5050");
+/// assert_eq!(calls.get(), 1);
+/// # }
+/// ```
+pub struct Lazy<T, F> {
+    value: OnceCell<T>,
+    f:     F,
+}
+
+impl<T, F> Lazy<T, F>
+where F: Fn() -> T {
+    pub fn new(f: F) -> Lazy<T, F> {
+        Lazy { value: OnceCell::new(), f }
+    }
+
+    fn get(&self) -> &T {
+        self.value.get_or_init(&self.f)
+    }
+}
+
+impl<T, F> Show for Lazy<T, F>
+where T: Show, F: Fn() -> T {
+    fn len(&self) -> usize { Show::len(self.get()) }
+    fn size_hint(&self) -> SizeHint { Show::size_hint(self.get()) }
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Show::write(self.get(), w)
+    }
+}
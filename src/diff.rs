@@ -0,0 +1,430 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! Line diffs between two texts, shown in unified diff format.
+//!
+//! A `Diff` finds the fewest lines to delete and insert to turn one text into the other, with
+//! Myers' algorithm in memory proportional to the texts' length, and shows them the way `diff -u`
+//! does: hunks of changed lines with a few lines of context around them. It's a `Show`, so it
+//! goes straight into `catln!` and the rest, or into a test's failure message. With `colored`,
+//! removed lines are red and added ones green, as far as the `style` module finds the terminal
+//! allows.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::diff::Diff;
+//!
+//! # fn main() {
+//! let old = "name = cats\nversion = 0.1\nedition = 2015\n";
+//! let new = "name = cats\nversion = 0.2\nedition = 2015\n";
+//!
+//! assert_eq!(scat!(Diff::new(old, new).names("a/Cargo.toml", "b/Cargo.toml")), "\
+//! --- a/Cargo.toml
+//! +++ b/Cargo.toml
+//! @@ -1,3 +1,3 @@
+//!  name = cats
+//! -version = 0.1
+//! +version = 0.2
+//!  edition = 2015
+//! ");
+//!
+//! // Texts that are the same have no hunks, and show as nothing at all.
+//! assert!(Diff::new(old, old).is_empty());
+//! assert_eq!(scat!(Diff::new(old, old)), "");
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::io;
+
+use style::{Color, Style};
+use traits::{Show, Format, measure, write_counted};
+
+const REMOVED: Style = Style::new().fg(Color::Red);
+const ADDED:   Style = Style::new().fg(Color::Green);
+const HUNK:    Style = Style::new().fg(Color::Cyan);
+const HEADER:  Style = Style::new().bold();
+
+// A line, and whether it ended with a newline. Only the last line of a text can be missing one,
+// and it then differs from the same line with one.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Line<'a> {
+    text:    &'a str,
+    newline: bool,
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+// The shortest edit script from `a` to `b`, by Myers' algorithm in its linear space form. Within
+// each run of changes, the deletions come before the insertions, as `diff -u` shows them.
+fn diff<T: PartialEq>(a: &[T], b: &[T]) -> Vec<Op> {
+    let mut search = Search::new(a.len() + b.len());
+    let mut ops    = Vec::with_capacity(a.len() + b.len());
+
+    search.compare(a, 0, b, 0, &mut ops);
+
+    let mut start = 0;
+
+    while start < ops.len() {
+        let run = ops[start..].iter().take_while(|op| !matches!(**op, Op::Equal(_))).count();
+
+        ops[start..start + run].sort_by_key(|op| matches!(*op, Op::Insert(_)));
+        start += run.max(1);
+    }
+
+    ops
+}
+
+// The frontiers of the forward and reverse searches for a middle snake: for each diagonal
+// `k = x - y`, the furthest either has reached on it, measured from its own end. One pair is big
+// enough for the whole diff, and is shared by every part of it in turn.
+struct Search {
+    forward: Vec<isize>,
+    reverse: Vec<isize>,
+    offset:  isize,
+}
+
+impl Search {
+    fn new(max: usize) -> Search {
+        let offset = (max / 2 + 2) as isize;
+
+        Search {
+            forward: vec![0; 2 * offset as usize + 1],
+            reverse: vec![0; 2 * offset as usize + 1],
+            offset,
+        }
+    }
+
+    // Push the ops turning `a` into `b` onto `ops`, where `a` starts at line `x` of the old text
+    // and `b` at line `y` of the new one.
+    fn compare<T>(&mut self, a: &[T], x: usize, b: &[T], y: usize, ops: &mut Vec<Op>)
+    where T: PartialEq {
+        let prefix = a.iter().zip(b).take_while(|&(a, b)| a == b).count();
+        let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev())
+            .take_while(|&(a, b)| a == b)
+            .count();
+
+        let (a_end, b_end) = (a.len() - suffix, b.len() - suffix);
+
+        ops.extend((x..x + prefix).map(Op::Equal));
+
+        if prefix == a_end {
+            ops.extend((y + prefix..y + b_end).map(Op::Insert));
+        } else if prefix == b_end {
+            ops.extend((x + prefix..x + a_end).map(Op::Delete));
+        } else {
+            // With their common ends gone and neither empty, they're at least two edits apart, so
+            // each side of the middle snake is a smaller diff than this one.
+            let (a, b)           = (&a[prefix..a_end], &b[prefix..b_end]);
+            let (x, y)           = (x + prefix, y + prefix);
+            let (sx, sy, ex, ey) = self.middle_snake(a, b);
+
+            self.compare(&a[..sx], x, &b[..sy], y, ops);
+            ops.extend((x + sx..x + ex).map(Op::Equal));
+            self.compare(&a[ex..], x + ex, &b[ey..], y + ey, ops);
+        }
+
+        ops.extend((x + a_end..x + a.len()).map(Op::Equal));
+    }
+
+    // The start and end of the snake that an optimal path from the start of `a` and `b` to their
+    // ends takes around its middle edit, found by searching from both ends at once until the
+    // searches meet.
+    fn middle_snake<T: PartialEq>(&mut self, a: &[T], b: &[T]) -> (usize, usize, usize, usize) {
+        let (n, m)  = (a.len() as isize, b.len() as isize);
+        let delta   = n - m;
+        let odd     = delta % 2 != 0;
+        let off     = self.offset;
+        let at      = |k: isize| (k + off) as usize;
+
+        self.forward[at(1)] = 0;
+        self.reverse[at(1)] = 0;
+
+        for d in 0..=(n + m + 1) / 2 {
+            for k in (-d..=d).step_by(2) {
+                let v     = &mut self.forward;
+                let mut x = if k == -d || (k != d && v[at(k - 1)] < v[at(k + 1)]) {
+                    v[at(k + 1)]
+                } else {
+                    v[at(k - 1)] + 1
+                };
+                let (x0, y0) = (x, x - k);
+
+                while x < n && x - k < m && a[x as usize] == b[(x - k) as usize] {
+                    x += 1;
+                }
+
+                v[at(k)] = x;
+
+                // The reverse search has done `d - 1` rounds, and runs along diagonal
+                // `delta - k` from its end.
+                let r = delta - k;
+
+                if odd && r.abs() < d && x + self.reverse[at(r)] >= n {
+                    return (x0 as usize, y0 as usize, x as usize, (x - k) as usize);
+                }
+            }
+
+            for r in (-d..=d).step_by(2) {
+                let v     = &mut self.reverse;
+                let mut x = if r == -d || (r != d && v[at(r - 1)] < v[at(r + 1)]) {
+                    v[at(r + 1)]
+                } else {
+                    v[at(r - 1)] + 1
+                };
+                let x0 = x;
+
+                while x < n && x - r < m
+                    && a[(n - 1 - x) as usize] == b[(m - 1 - (x - r)) as usize] {
+                    x += 1;
+                }
+
+                v[at(r)] = x;
+
+                let k = delta - r;
+
+                if !odd && k.abs() <= d && self.forward[at(k)] + x >= n {
+                    let (sx, ex) = (n - x, n - x0);
+
+                    return (sx as usize, (sx - k) as usize, ex as usize, (ex - k) as usize);
+                }
+            }
+        }
+
+        unreachable!("the searches for a middle snake always meet")
+    }
+}
+
+// How many lines of the old text `ops` cover.
+fn old_lines(ops: &[Op]) -> usize {
+    ops.iter().filter(|op| !matches!(**op, Op::Insert(_))).count()
+}
+
+// How many lines of the new text `ops` cover.
+fn new_lines(ops: &[Op]) -> usize {
+    ops.iter().filter(|op| !matches!(**op, Op::Delete(_))).count()
+}
+
+fn split(text: &str) -> Vec<Line<'_>> {
+    text.split_inclusive('\n').map(|l| match l.strip_suffix('\n') {
+        Some(text) => Line { text, newline: true },
+        None       => Line { text: l, newline: false },
+    }).collect()
+}
+
+// One line of a diff, after its marker.
+struct Marked<'a>(&'a str, &'a str);
+
+impl<'a> Show for Marked<'a> {
+    fn len(&self) -> usize { self.0.len() + self.1.len() }
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Ok(Show::write(self.0, w)? + Show::write(self.1, w)?)
+    }
+}
+
+/// The differences between two texts, line by line. See the module documentation.
+pub struct Diff<'a> {
+    old:     Vec<Line<'a>>,
+    new:     Vec<Line<'a>>,
+    ops:     Vec<Op>,
+    context: usize,
+    colored: bool,
+    names:   Option<(&'a str, &'a str)>,
+}
+
+impl<'a> Diff<'a> {
+    /// The diff from the text `old` to the text `new`.
+    pub fn new(old: &'a str, new: &'a str) -> Diff<'a> {
+        Diff::from_lines(split(old), split(new))
+    }
+
+    /// The diff between two sequences of lines, which shouldn't include their newlines.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[macro_use] extern crate cats;
+    /// # use cats::diff::Diff;
+    /// # fn main() {
+    /// let old = ["a", "b", "c", "d", "e", "f", "g", "h", "i"];
+    /// let new = ["a", "b", "c", "d", "E", "f", "g", "h", "i", "j"];
+    ///
+    /// assert_eq!(scat!(Diff::lines(old, new).context(1)), "\
+    /// @@ -4,3 +4,3 @@
+    ///  d
+    /// -e
+    /// +E
+    ///  f
+    /// @@ -9 +9,2 @@
+    ///  i
+    /// +j
+    /// ");
+    /// # }
+    /// ```
+    pub fn lines<I, J>(old: I, new: J) -> Diff<'a>
+    where I: IntoIterator<Item = &'a str>, J: IntoIterator<Item = &'a str> {
+        let line = |text| Line { text, newline: true };
+
+        Diff::from_lines(old.into_iter().map(line).collect(), new.into_iter().map(line).collect())
+    }
+
+    fn from_lines(old: Vec<Line<'a>>, new: Vec<Line<'a>>) -> Diff<'a> {
+        // Number the distinct lines, so that the search compares numbers instead of strings.
+        let mut ids = HashMap::new();
+        let mut id  = |line: &Line<'a>| {
+            let next = ids.len();
+
+            *ids.entry(*line).or_insert(next)
+        };
+
+        let old_ids: Vec<usize> = old.iter().map(&mut id).collect();
+        let new_ids: Vec<usize> = new.iter().map(&mut id).collect();
+
+        let ops = diff(&old_ids, &new_ids);
+
+        Diff { old, new, ops, context: 3, colored: false, names: None }
+    }
+
+    /// Show `n` unchanged lines around each change, instead of 3.
+    pub fn context(self, n: usize) -> Diff<'a> {
+        Diff { context: n, .. self }
+    }
+
+    /// Color removed lines red, added ones green and the hunk headers cyan.
+    pub fn colored(self) -> Diff<'a> {
+        Diff { colored: true, .. self }
+    }
+
+    /// Start with `---` and `+++` lines naming the old and new texts.
+    pub fn names(self, old: &'a str, new: &'a str) -> Diff<'a> {
+        Diff { names: Some((old, new)), .. self }
+    }
+
+    /// Whether the texts are the same.
+    pub fn is_empty(&self) -> bool {
+        self.ops.iter().all(|op| matches!(*op, Op::Equal(..)))
+    }
+
+    // The ranges of `ops` that make up each hunk: every change, with up to `context` lines
+    // around it, and hunks that would touch or overlap merged into one.
+    fn hunks(&self) -> Vec<(usize, usize)> {
+        let changes = self.ops.iter().enumerate().filter(|&(_, op)| !matches!(*op, Op::Equal(..)));
+
+        let mut hunks: Vec<(usize, usize)> = Vec::new();
+
+        for (i, _) in changes {
+            let start = i.saturating_sub(self.context);
+            let end   = (i + 1 + self.context).min(self.ops.len());
+
+            match hunks.last_mut() {
+                Some(last) if start <= last.1 => last.1 = end,
+                _                             => hunks.push((start, end)),
+            }
+        }
+
+        hunks
+    }
+
+    fn style(&self, style: Style) -> Style {
+        if self.colored { style } else { Style::new() }
+    }
+
+    // Write a line with its marker, then its newline, or a note that there wasn't one.
+    fn write_line<W: io::Write>(&self, style: Style, marker: &str, line: &Line, w: &mut W)
+    -> io::Result<()> {
+        Format::write(&self.style(style), &Marked(marker, line.text), w)?;
+
+        match line.newline {
+            true  => w.write_all(b"\n"),
+            false => w.write_all(b"\n\\ No newline at end of file\n"),
+        }
+    }
+
+    fn write_range<W: io::Write>(&self, marker: char, start: usize, count: usize, w: &mut W)
+    -> io::Result<()> {
+        // An empty range is given by the line before it, as in "-0,0" for an empty text.
+        let first = if count == 0 { start } else { start + 1 };
+
+        Show::write(&marker, w)?;
+        Show::write(&first, w)?;
+
+        if count != 1 {
+            Show::write(&',', w)?;
+            Show::write(&count, w)?;
+        }
+
+        Ok(())
+    }
+
+    fn lay_out<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let hunks = self.hunks();
+
+        if hunks.is_empty() {
+            return Ok(());
+        }
+
+        if let Some((old, new)) = self.names {
+            Format::write(&self.style(HEADER), &Marked("--- ", old), w)?;
+            w.write_all(b"\n")?;
+            Format::write(&self.style(HEADER), &Marked("+++ ", new), w)?;
+            w.write_all(b"\n")?;
+        }
+
+        for (start, end) in hunks {
+            let ops = &self.ops[start..end];
+
+            // Where the hunk starts in each text, and how many of its lines it covers.
+            let (old_start, old_count) = (old_lines(&self.ops[..start]), old_lines(ops));
+            let (new_start, new_count) = (new_lines(&self.ops[..start]), new_lines(ops));
+
+            let mut header = Vec::new();
+
+            header.extend_from_slice(b"@@ ");
+            self.write_range('-', old_start, old_count, &mut header)?;
+            header.push(b' ');
+            self.write_range('+', new_start, new_count, &mut header)?;
+            header.extend_from_slice(b" @@");
+
+            Format::write(&self.style(HUNK), &String::from_utf8_lossy(&header).into_owned(), w)?;
+            w.write_all(b"\n")?;
+
+            for op in ops {
+                match *op {
+                    Op::Equal(i)    => self.write_line(Style::new(), " ", &self.old[i], w)?,
+                    Op::Delete(i)   => self.write_line(REMOVED, "-", &self.old[i], w)?,
+                    Op::Insert(j)   => self.write_line(ADDED, "+", &self.new[j], w)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Show for Diff<'a> {
+    fn len(&self) -> usize {
+        measure(|w| self.lay_out(w))
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| self.lay_out(w))
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::string::FromUtf8Error;
+
+/// The error from `try_cat!` and `try_catln!`, saying which argument of the cat failed to write
+/// and how much had been written before it.
+///
+/// Converts into an `io::Error` of the same kind, so `?` still works in functions that return
+/// `io::Result`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use std::io;
+///
+/// struct Full(usize);
+///
+/// impl io::Write for Full {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         if self.0 < buf.len() { return Err(io::ErrorKind::WriteZero.into()); }
+///
+///         self.0 -= buf.len();
+///         Ok(buf.len())
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> { Ok(()) }
+/// }
+///
+/// # fn main() {
+/// cats::set_stdout(Some(Box::new(Full(5))));
+///
+/// let err = try_cat!("Meow", ", ", "World").unwrap_err();
+///
+/// assert_eq!(err.segment, 1);
+/// assert_eq!(err.written, 4);
+/// assert_eq!(err.error.kind(), io::ErrorKind::WriteZero);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CatError {
+    /// The position, counting from zero, of the argument that failed.
+    pub segment: usize,
+
+    /// The bytes written by the arguments before it.
+    pub written: usize,
+
+    /// The underlying error.
+    pub error: io::Error,
+}
+
+impl fmt::Display for CatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cat failed at argument {} after {} bytes: {}", self.segment, self.written,
+               self.error)
+    }
+}
+
+impl error::Error for CatError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<CatError> for io::Error {
+    fn from(e: CatError) -> io::Error {
+        io::Error::new(e.error.kind(), e)
+    }
+}
+
+/// The error from `try_scat!`: either an argument failed to write, as a field whose `Overflow` is
+/// `Error` does when its value is too wide, or the cat wasn't valid utf-8.
+///
+/// Converts into an `io::Error`, with invalid utf-8 as `io::ErrorKind::InvalidData`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use cats::{Pad, Align, Overflow, FieldOverflow, ScatError};
+///
+/// # fn main() {
+/// const NAME: Pad = Pad { width: 4, fill: ' ', align: Align::Left, overflow: Overflow::Error };
+///
+/// match try_scat!(NAME; "Mittens") {
+///     Err(ScatError::Write(e)) => {
+///         let overflow = e.get_ref().and_then(|e| e.downcast_ref::<FieldOverflow>());
+///
+///         assert_eq!(overflow, Some(&FieldOverflow { width: 4, chars: 7 }));
+///     },
+///     other => panic!("expected an overflow, got {:?}", other),
+/// }
+///
+/// assert!(matches!(try_scat!(cats::Raw(&[0xff][..])), Err(ScatError::Utf8(_))));
+/// # }
+/// ```
+#[derive(Debug)]
+pub enum ScatError {
+    /// An argument of the cat failed to write.
+    Write(io::Error),
+
+    /// The cat was written, but isn't valid utf-8.
+    Utf8(FromUtf8Error),
+}
+
+impl fmt::Display for ScatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ScatError::Write(ref e) => e.fmt(f),
+            ScatError::Utf8(ref e)  => write!(f, "cat generated invalid utf-8: {}", e),
+        }
+    }
+}
+
+impl error::Error for ScatError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            ScatError::Write(ref e) => Some(e),
+            ScatError::Utf8(ref e)  => Some(e),
+        }
+    }
+}
+
+impl From<ScatError> for io::Error {
+    fn from(e: ScatError) -> io::Error {
+        match e {
+            ScatError::Write(e) => e,
+            ScatError::Utf8(e)  => io::Error::new(io::ErrorKind::InvalidData, e),
+        }
+    }
+}
+
+// Unwrap the bytes written by a cat into memory, for the macros and methods that don't return a
+// `Result`. Memory itself never refuses a write, so the only errors come from the cat's arguments,
+// such as a field whose `Overflow` is `Error`.
+#[doc(hidden)]
+#[track_caller]
+pub fn __expect_written<T>(result: io::Result<T>, what: &str) -> T {
+    match result {
+        Ok(t)  => t,
+        Err(e) => panic!("{} failed to write: {}", what, e),
+    }
+}
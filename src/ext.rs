@@ -0,0 +1,67 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use pad::Pad;
+use spec::Width;
+use style::Style;
+use text::Quote;
+use traits::{Show, Format, Formatted, FormattedInt, Rep, HEX};
+
+/// Chainable adapters for anything that implements `Show`. Each one borrows the value and returns
+/// a `Formatted` wrapper, which is itself `Show`, so adapters can be stacked and used directly as
+/// arguments to the cat macros.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::ShowExt;
+/// # fn main() {
+/// let s = scat!('|', "ab".padded(4), '|', 255u8.hexed(), '|', '-'.repeated(3), '|', "x".quoted());
+///
+/// assert_eq!(s, "|ab  |ff|---|\"x\"");
+/// # }
+/// ```
+pub trait ShowExt: Show + Sized {
+    /// Left-align in a field at least `width` characters wide, filling with spaces.
+    fn padded<'x>(&'x self, width: usize) -> Formatted<'x, Pad, Self> {
+        Formatted(Pad::from(Width::new(width)), self)
+    }
+
+    /// Show an integer in lowercase hexadecimal, without a prefix.
+    fn hexed<'x>(&'x self) -> Formatted<'x, FormattedInt<'static>, Self>
+    where FormattedInt<'static>: Format<Self> {
+        Formatted(HEX, self)
+    }
+
+    /// Show the value `n` times in a row.
+    fn repeated<'x>(&'x self, n: usize) -> Formatted<'x, Rep, Self> {
+        Formatted(Rep(n), self)
+    }
+
+    /// Surround the value with double quotes. Nothing inside is escaped.
+    fn quoted<'x>(&'x self) -> Formatted<'x, Quote, Self> {
+        Formatted(Quote('"'), self)
+    }
+
+    /// Show the value in `style`, as far as the terminal allows.
+    fn styled<'x>(&'x self, style: Style) -> Formatted<'x, Style, Self> {
+        Formatted(style, self)
+    }
+}
+
+impl<T> ShowExt for T
+where T: Show {}
@@ -0,0 +1,118 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cmp;
+use std::fmt;
+use std::io;
+use std::str;
+
+/// Adapts a `fmt::Write`, such as a `fmt::Formatter` or a `String`, into an `io::Write` that cats
+/// can write to. This is what `cat_to!` uses.
+///
+/// A character whose bytes are split across two writes is held back until the rest of it
+/// arrives. Bytes that aren't utf-8 are an error.
+pub struct FmtWriter<'a, F: fmt::Write + ?Sized + 'a> {
+    inner: &'a mut F,
+
+    // The first bytes of a character that was split across two writes.
+    partial:     [u8; 4],
+    partial_len: usize,
+}
+
+impl<'a, F: fmt::Write + ?Sized + 'a> FmtWriter<'a, F> {
+    pub fn new(inner: &'a mut F) -> FmtWriter<'a, F> {
+        FmtWriter { inner, partial: [0; 4], partial_len: 0 }
+    }
+
+    /// Check that nothing was left over from a split character.
+    pub fn finish(self) -> fmt::Result {
+        if self.partial_len == 0 { Ok(()) } else { Err(fmt::Error) }
+    }
+
+    fn write_str(&mut self, s: &str) -> io::Result<()> {
+        self.inner.write_str(s).map_err(|_| io::Error::new(io::ErrorKind::Other, "formatter error"))
+    }
+
+    // Feed bytes into a split character. Returns how many were used.
+    fn continue_partial(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let width = match self.partial[0] {
+            b if b >= 0xf0 => 4,
+            b if b >= 0xe0 => 3,
+            _              => 2,
+        };
+
+        let used = cmp::min(width - self.partial_len, buf.len());
+
+        self.partial[self.partial_len..self.partial_len + used].copy_from_slice(&buf[..used]);
+        self.partial_len += used;
+
+        if self.partial_len == width {
+            self.partial_len = 0;
+
+            let partial = self.partial;
+
+            match str::from_utf8(&partial[..width]) {
+                Ok(s)  => self.write_str(s)?,
+                Err(_) => return Err(invalid_data()),
+            }
+        }
+
+        Ok(used)
+    }
+}
+
+fn invalid_data() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "cat wrote invalid utf-8")
+}
+
+impl<'a, F: fmt::Write + ?Sized + 'a> io::Write for FmtWriter<'a, F> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.partial_len > 0 {
+            return self.continue_partial(buf);
+        }
+
+        match str::from_utf8(buf) {
+            Ok(s) => {
+                self.write_str(s)?;
+
+                Ok(buf.len())
+            },
+            Err(e) => {
+                let valid = e.valid_up_to();
+
+                // Safe because `from_utf8` just checked these bytes.
+                self.write_str(unsafe { str::from_utf8_unchecked(&buf[..valid]) })?;
+
+                match e.error_len() {
+                    // The buffer ends partway through a character.
+                    None => {
+                        let rest = &buf[valid..];
+
+                        self.partial[..rest.len()].copy_from_slice(rest);
+                        self.partial_len = rest.len();
+
+                        Ok(buf.len())
+                    },
+                    Some(_) if valid > 0 => Ok(valid),
+                    Some(_)              => Err(invalid_data()),
+                }
+            },
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
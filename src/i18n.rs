@@ -0,0 +1,305 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! Message catalogs, for translating what a program prints.
+//!
+//! A `Catalog` holds one locale's messages, each a `Template` under a key. It's usually parsed
+//! from text in a small Fluent-like format:
+//!
+//! ```text
+//! # Comments start with '#'.
+//! greeting = Hello {name}!
+//! inbox    = You have {n} new messages.
+//! signoff  = Best wishes,
+//!     The {team} team
+//! ```
+//!
+//! where a message continues on any following indented lines, joined with newlines. `Catalogs`
+//! gathers them up by locale, and `Catalogs::select` picks the messages for the locales a user
+//! prefers, falling back from "fr-CA" to "fr" and finally to the first catalog added. A filled-in
+//! message is a `Show`, so it goes straight into `catln!` and the rest.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::i18n::{Catalog, Catalogs, Error};
+//!
+//! # fn main() {
+//! let mut catalogs = Catalogs::new();
+//!
+//! catalogs.add("en", Catalog::parse("greeting = Hello {name}!\nbye = Bye!").unwrap());
+//! catalogs.add("fr", Catalog::parse("greeting = Bonjour {name} !").unwrap());
+//!
+//! let messages = catalogs.select(&["fr-CA", "en-GB"]);
+//!
+//! assert_eq!(messages.render("greeting", &[("name", "Tom")]).unwrap(), "Bonjour Tom !");
+//!
+//! // Messages missing from the French catalog come from the English one.
+//! assert_eq!(scat!(messages.message("bye", &()).unwrap()), "Bye!");
+//!
+//! let signoff = Catalog::parse("# Letters\nsignoff = Best wishes,\n    The {team} team").unwrap();
+//!
+//! assert_eq!(signoff.get("signoff").unwrap().render(&[("team", "cats")]).unwrap(),
+//!            "Best wishes,\nThe cats team");
+//! assert_eq!(Catalog::parse("greeting Hello").unwrap_err(), Error::Syntax { line: 1 });
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::io;
+
+use plural::PluralRules;
+use template::{self, Filled, Lookup, Template, TemplateError};
+
+/// What went wrong loading a catalog or showing one of its messages.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// This line of a catalog, counting from 1, isn't a comment, a `key = message` or a
+    /// continuation of the message before it.
+    Syntax { line: usize },
+
+    /// The message starting on this line of a catalog isn't a valid `Template`.
+    Template { line: usize, error: TemplateError },
+
+    /// None of the selected catalogs has a message with this key.
+    NoMessage(String),
+
+    /// The message was found, but one of its placeholders had no value.
+    Fill(TemplateError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Syntax { line }              => write!(f, "syntax error on line {}", line),
+            Error::Template { line, ref error } => write!(f, "{} on line {}", error, line),
+            Error::NoMessage(ref key)           => write!(f, "no message for key `{}`", key),
+            Error::Fill(ref error)              => error.fmt(f),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+/// The messages for one locale, each a `Template` under a key.
+#[derive(Clone, Debug, Default)]
+pub struct Catalog {
+    messages: HashMap<String, Template>,
+}
+
+impl Catalog {
+    pub fn new() -> Catalog {
+        Catalog::default()
+    }
+
+    /// Parse a catalog in the format described in the module documentation. A key given twice
+    /// keeps its last message.
+    pub fn parse(source: &str) -> Result<Catalog, Error> {
+        let mut catalog = Catalog::new();
+
+        // The key of the message being read, the line it started on, and its text so far.
+        let mut current: Option<(&str, usize, String)> = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(char::is_whitespace) {
+                match current {
+                    Some((_, _, ref mut text)) => { text.push('\n'); text.push_str(trimmed); },
+                    None                       => return Err(Error::Syntax { line: i + 1 }),
+                }
+
+                continue;
+            }
+
+            let (key, text) = match line.split_once('=') {
+                Some((key, text)) if template::valid_name(key.trim()) => (key.trim(), text.trim()),
+                _ => return Err(Error::Syntax { line: i + 1 }),
+            };
+
+            if let Some(done) = current.replace((key, i + 1, text.into())) {
+                catalog.insert_parsed(done)?;
+            }
+        }
+
+        if let Some(done) = current {
+            catalog.insert_parsed(done)?;
+        }
+
+        Ok(catalog)
+    }
+
+    fn insert_parsed(&mut self, (key, line, text): (&str, usize, String)) -> Result<(), Error> {
+        let template = Template::parse(&text).map_err(|error| Error::Template { line, error })?;
+
+        self.insert(key, template);
+
+        Ok(())
+    }
+
+    /// Add a message, replacing any already under `key`.
+    pub fn insert<K: Into<String>>(&mut self, key: K, template: Template) {
+        self.messages.insert(key.into(), template);
+    }
+
+    /// The message under `key`, if there is one.
+    pub fn get(&self, key: &str) -> Option<&Template> {
+        self.messages.get(key)
+    }
+}
+
+/// Catalogs for any number of locales. The first one added is the fallback for messages that
+/// none of the preferred locales have.
+#[derive(Clone, Debug, Default)]
+pub struct Catalogs {
+    catalogs: Vec<(String, Catalog)>,
+}
+
+// Locales are compared ignoring case, and with '_' the same as '-', so "en_US" matches "en-us".
+fn same_locale(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.chars().zip(b.chars()).all(|(a, b)| {
+        let norm = |c: char| if c == '_' { '-' } else { c.to_ascii_lowercase() };
+
+        norm(a) == norm(b)
+    })
+}
+
+// The language part of a locale, as in "fr" for "fr-CA".
+fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+impl Catalogs {
+    pub fn new() -> Catalogs {
+        Catalogs::default()
+    }
+
+    /// Add the catalog for `locale`, replacing any already there.
+    pub fn add<L: Into<String>>(&mut self, locale: L, catalog: Catalog) {
+        let locale = locale.into();
+
+        match self.catalogs.iter_mut().find(|c| same_locale(&c.0, &locale)) {
+            Some(c) => c.1 = catalog,
+            None    => self.catalogs.push((locale, catalog)),
+        }
+    }
+
+    fn find(&self, locale: &str) -> Option<&(String, Catalog)> {
+        self.catalogs.iter().find(|c| same_locale(&c.0, locale))
+    }
+
+    /// The messages for a user who prefers the locales in `preferred`, best first. Each locale is
+    /// tried as given and then by its language alone, and the first catalog added comes last.
+    pub fn select(&self, preferred: &[&str]) -> Messages<'_> {
+        let mut chain: Vec<&(String, Catalog)> = Vec::new();
+
+        let wanted = preferred.iter()
+            .flat_map(|&l| vec![l, language(l)])
+            .filter_map(|l| self.find(l))
+            .chain(self.catalogs.first());
+
+        for catalog in wanted {
+            if !chain.iter().any(|&c| std::ptr::eq(c, catalog)) {
+                chain.push(catalog);
+            }
+        }
+
+        Messages { chain }
+    }
+}
+
+/// The messages chosen by `Catalogs::select`.
+pub struct Messages<'c> {
+    chain: Vec<&'c (String, Catalog)>,
+}
+
+impl<'c> Messages<'c> {
+    /// The message under `key` from the most preferred catalog that has it.
+    pub fn get(&self, key: &str) -> Option<&'c Template> {
+        self.chain.iter().find_map(|c| c.1.get(key))
+    }
+
+    /// The message under `key`, filled in with `values`.
+    pub fn message<'v, V>(&self, key: &str, values: &'v V) -> Result<Filled<'c, 'v>, Error>
+    where V: Lookup + ?Sized {
+        let template = self.get(key).ok_or_else(|| Error::NoMessage(key.into()))?;
+
+        template.fill(values).map_err(Error::Fill)
+    }
+
+    /// The message under `key`, filled in with `values` and collected into a `String`.
+    pub fn render<V>(&self, key: &str, values: &V) -> Result<String, Error>
+    where V: Lookup + ?Sized {
+        let template = self.get(key).ok_or_else(|| Error::NoMessage(key.into()))?;
+
+        template.render(values).map_err(Error::Fill)
+    }
+
+    /// The form of the message under `key` for a count of `n`, by the plural rules of the
+    /// catalog it comes from. That's the message under `key.one`, `key.few` and so on for `n`'s
+    /// category, or else `key.other`, or else `key` itself.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[macro_use] extern crate cats;
+    /// use cats::i18n::{Catalog, Catalogs};
+    ///
+    /// # fn main() {
+    /// let mut catalogs = Catalogs::new();
+    ///
+    /// catalogs.add("en", Catalog::parse("inbox.one   = You have {n} new message.\n\
+    ///     inbox.other = You have {n} new messages.").unwrap());
+    /// catalogs.add("ru", Catalog::parse("inbox.one   = У вас {n} новое сообщение.\n\
+    ///     inbox.few   = У вас {n} новых сообщения.\n\
+    ///     inbox.many  = У вас {n} новых сообщений.").unwrap());
+    ///
+    /// let (en, ru) = (catalogs.select(&["en"]), catalogs.select(&["ru"]));
+    ///
+    /// assert_eq!(scat!(en.plural("inbox", 1, &[("n", 1)]).unwrap()), "You have 1 new message.");
+    /// assert_eq!(scat!(en.plural("inbox", 3, &[("n", 3)]).unwrap()), "You have 3 new messages.");
+    /// assert_eq!(scat!(ru.plural("inbox", 3, &[("n", 3)]).unwrap()),
+    ///            "У вас 3 новых сообщения.");
+    /// assert_eq!(scat!(ru.plural("inbox", 25, &[("n", 25)]).unwrap()),
+    ///            "У вас 25 новых сообщений.");
+    /// # }
+    /// ```
+    pub fn plural<'v, V>(&self, key: &str, n: u64, values: &'v V) -> Result<Filled<'c, 'v>, Error>
+    where V: Lookup + ?Sized {
+        let template = self.chain.iter().find_map(|c| {
+            let category = PluralRules::for_locale(&c.0).category(n);
+
+            c.1.get(&format!("{}.{}", key, category.name()))
+                .or_else(|| c.1.get(&format!("{}.other", key)))
+                .or_else(|| c.1.get(key))
+        });
+
+        let template = template.ok_or_else(|| Error::NoMessage(key.into()))?;
+
+        template.fill(values).map_err(Error::Fill)
+    }
+}
@@ -0,0 +1,71 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cell::Cell;
+use std::io;
+
+use traits::{Show, Format, Rep};
+
+thread_local! {
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+// Spaces per level of indentation.
+const UNIT: usize = 4;
+
+/// Indent everything shown with `Indent` or `icat!` on this thread by one more level.
+pub fn push_indent() {
+    DEPTH.with(|d| d.set(d.get() + 1));
+}
+
+/// Undo a `push_indent`. Popping more levels than were pushed leaves the depth at zero.
+pub fn pop_indent() {
+    DEPTH.with(|d| d.set(d.get().saturating_sub(1)));
+}
+
+/// Show `self.0` levels of indentation, on top of however many have been pushed with
+/// `push_indent`. Each level is four spaces.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Indent;
+/// # fn main() {
+/// cats::push_indent();
+///
+/// assert_eq!(scat!(Indent(1), "fur"), "        fur");
+///
+/// cats::pop_indent();
+/// # }
+/// ```
+pub struct Indent(pub usize);
+
+impl Indent {
+    fn spaces(&self) -> usize {
+        (DEPTH.with(|d| d.get()) + self.0) * UNIT
+    }
+}
+
+impl Show for Indent {
+    fn len(&self) -> usize {
+        self.spaces()
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Format::write(&Rep(self.spaces()), &' ', w)
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+use std::io;
+
+use serde_json::{Number, Value};
+
+use bridge::Disp;
+use traits::{Show, Format, Rep, measure, write_counted};
+
+/// Show a `serde_json::Value` as JSON, either compact, as `serde_json::to_string` writes it, or
+/// pretty, with each element on a line of its own, indented by `indent` spaces per level, as
+/// `serde_json::to_string_pretty` writes it when `indent` is 2.
+///
+/// A `Value` is also a `Show` by itself, in the compact form.
+/// Either way the length is found with a pass that writes nothing, so a large document is put out
+/// without the buffer growing along the way. Requires the `serde_json` feature.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # extern crate serde_json;
+/// use cats::Json;
+/// use serde_json::json;
+///
+/// # fn main() {
+/// let cat = json!({ "name": "Tom", "lives": 9, "toys": ["mouse", "string"], "owner": null });
+///
+/// assert_eq!(scat!(Json::COMPACT; cat),
+///            r#"{"lives":9,"name":"Tom","owner":null,"toys":["mouse","string"]}"#);
+///
+/// assert_eq!(scat!(Json::PRETTY; cat), r#"{
+///   "lives": 9,
+///   "name": "Tom",
+///   "owner": null,
+///   "toys": [
+///     "mouse",
+///     "string"
+///   ]
+/// }"#);
+///
+/// assert_eq!(scat!(Json::PRETTY; cat), serde_json::to_string_pretty(&cat).unwrap());
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Json {
+    pub indent: Option<usize>,
+}
+
+impl Json {
+    /// Everything on one line, without spaces.
+    pub const COMPACT: Json = Json { indent: None };
+
+    /// Indented by two spaces per level.
+    pub const PRETTY: Json = Json { indent: Some(2) };
+
+    // Start a new line, indented for `depth`, if this is pretty.
+    fn newline<W: io::Write>(&self, depth: usize, w: &mut W) -> io::Result<()> {
+        if let Some(indent) = self.indent {
+            w.write_all(b"\n")?;
+            Format::write(&Rep(indent * depth), &' ', w)?;
+        }
+
+        Ok(())
+    }
+
+    // Write the elements of an array or object between `open` and `close`, each with `element`.
+    fn elements<I, T, F, W>(&self, open: u8, close: u8, items: I, depth: usize, w: &mut W,
+                            mut element: F)
+    -> io::Result<()>
+    where I: ExactSizeIterator<Item = T>, F: FnMut(T, &mut W) -> io::Result<()>, W: io::Write {
+        w.write_all(&[open])?;
+
+        // An empty one stays on one line, even when pretty.
+        if items.len() > 0 {
+            for (i, item) in items.enumerate() {
+                if i > 0 {
+                    w.write_all(b",")?;
+                }
+
+                self.newline(depth + 1, w)?;
+                element(item, w)?;
+            }
+
+            self.newline(depth, w)?;
+        }
+
+        w.write_all(&[close])
+    }
+
+    fn value<W: io::Write>(&self, value: &Value, depth: usize, w: &mut W) -> io::Result<()> {
+        match *value {
+            Value::Null          => w.write_all(b"null"),
+            Value::Bool(b)       => w.write_all(if b { b"true" } else { b"false" }),
+            Value::Number(ref n) => number(n, w),
+            Value::String(ref s) => string(s, w),
+            Value::Array(ref a)  => self.elements(b'[', b']', a.iter(), depth, w, |v, w| {
+                self.value(v, depth + 1, w)
+            }),
+            Value::Object(ref o) => self.elements(b'{', b'}', o.iter(), depth, w, |(k, v), w| {
+                string(k, w)?;
+                w.write_all(if self.indent.is_some() { b": " } else { b":" })?;
+                self.value(v, depth + 1, w)
+            }),
+        }
+    }
+}
+
+fn number<W: io::Write>(n: &Number, w: &mut W) -> io::Result<()> {
+    match (n.as_u64(), n.as_i64()) {
+        (Some(u), _) => Show::write(&u, w).map(|_| ()),
+        (_, Some(i)) => Show::write(&i, w).map(|_| ()),
+        _            => Show::write(&Disp(n), w).map(|_| ()),
+    }
+}
+
+// Write `s` as a JSON string, escaping what JSON requires and nothing else.
+fn string<W: io::Write>(s: &str, w: &mut W) -> io::Result<()> {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+
+    let bytes     = s.as_bytes();
+    let mut start = 0;
+
+    w.write_all(b"\"")?;
+
+    for (i, &b) in bytes.iter().enumerate() {
+        let short: &[u8] = match b {
+            b'"'         => b"\\\"",
+            b'\\'        => b"\\\\",
+            b'\n'        => b"\\n",
+            b'\r'        => b"\\r",
+            b'\t'        => b"\\t",
+            0x08         => b"\\b",
+            0x0c         => b"\\f",
+            0x00..=0x1f  => b"",
+            _            => continue,
+        };
+
+        w.write_all(&bytes[start..i])?;
+        start = i + 1;
+
+        if short.is_empty() {
+            w.write_all(b"\\u00")?;
+            w.write_all(&[HEX[(b >> 4) as usize], HEX[(b & 0xf) as usize]])?;
+        } else {
+            w.write_all(short)?;
+        }
+    }
+
+    w.write_all(&bytes[start..])?;
+    w.write_all(b"\"")
+}
+
+impl Format<Value> for Json {
+    fn len(&self, value: &Value) -> usize {
+        measure(|w| self.value(value, 0, w))
+    }
+
+    fn write<W: io::Write>(&self, value: &Value, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| self.value(value, 0, w))
+    }
+}
+
+impl Show for Value {
+    fn len(&self) -> usize { Format::len(&Json::COMPACT, self) }
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Format::write(&Json::COMPACT, self, w)
+    }
+}
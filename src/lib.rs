@@ -15,177 +15,1585 @@
 // DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
-#![feature(unicode)]
+extern crate cats_macros;
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+pub extern crate log as __log;
+
+#[cfg(feature = "tokio")]
+#[doc(hidden)]
+pub extern crate tokio as __tokio;
+
+#[cfg(feature = "serde_json")]
+extern crate serde_json;
+
+#[cfg(unix)]
+extern crate libc;
+
+#[cfg(windows)]
+extern crate windows_sys;
 
 mod traits;
+mod text;
+mod num;
+mod bytes;
+mod pad;
+mod spec;
+pub mod table;
+mod fmt_writer;
+mod sink;
+mod indent;
+mod combinators;
+mod ext;
+mod bridge;
+mod error;
+mod small;
+mod slice;
+mod target;
+mod writer;
+mod vectored;
+mod template;
+mod plural;
+pub mod printf;
+pub mod i18n;
+pub mod tree;
+pub mod progress;
+pub mod style;
+pub mod term;
+pub mod diff;
+#[cfg(feature = "tokio")]
+mod async_write;
+#[cfg(feature = "serde_json")]
+mod json;
+
+pub use traits::{Show, ShowDyn, Format, SignPolicy, PadPosition, Utf8Write, FormattedInt, Formatted,
+                 Rep, RepSep, SizeHint, CountingWriter, HEX, UPPER_HEX,
+                 ARABIC_INDIC_DIGITS, EASTERN_ARABIC_INDIC_DIGITS, DEVANAGARI_DIGITS, ARABIC_INDIC,
+                 EASTERN_ARABIC_INDIC, DEVANAGARI};
+pub use text::{Wrap, ExpandTabs, Masked, Quote, FormattedStr};
+pub use num::{DecimalAlign, Plural, FixedPoint, HexWidth, Fraction};
+pub use bytes::{Uuid, MacAddr, RgbHex, HexBytes, Raw};
+pub use pad::Pad;
+pub use spec::{Align, Width, Precision, Overflow, FieldOverflow};
+pub use combinators::{Then, Compose, Map, FnFormat, FnShow, Lazy};
+pub use ext::ShowExt;
+pub use bridge::{Disp, Dbg};
+pub use error::{CatError, ScatError};
+pub use small::CatString;
+pub use slice::{SliceWriter, BufferFull};
+pub use target::CatTarget;
+pub use writer::CatWriter;
+pub use template::{Template, TemplateError, Lookup, Filled};
+pub use plural::{PluralCategory, PluralRules, PluralForms};
+pub use fmt_writer::FmtWriter;
+pub use sink::{set_stdout, set_stderr, set_buffered};
+pub use indent::{Indent, push_indent, pop_indent};
+#[cfg(feature = "tokio")]
+pub use async_write::WriteCat;
+#[cfg(feature = "serde_json")]
+pub use json::Json;
+#[cfg(feature = "tokio")]
+#[doc(hidden)]
+pub use async_write::AsyncByRef as __AsyncByRef;
+
+#[doc(hidden)]
+pub use cats_macros::{__catf, __cat_args, __cat_check};
+#[doc(hidden)]
+pub use table::__write_table;
+#[doc(hidden)]
+pub use small::__CatBuf;
+#[doc(hidden)]
+pub use error::__expect_written;
+#[doc(hidden)]
+pub use slice::__is_buffer_full;
+#[doc(hidden)]
+pub use target::{__IoTarget, __FmtTarget};
+#[doc(hidden)]
+pub use writer::{__write_show, __write_format, __show_literal, __Batch};
+#[doc(hidden)]
+pub use vectored::__Gather;
+#[doc(hidden)]
+pub use sink::{__stdout, __stderr, __flush_stdout, __Scratch};
+
+// `catf!` calls these with each argument, spanned at the argument, so a type that can't be shown
+// is reported where the user wrote it rather than somewhere inside the expansion.
+#[doc(hidden)]
+pub fn __assert_show<T: ?Sized + Show>(_: &T) {}
+
+#[doc(hidden)]
+pub fn __assert_format<F: Format<T>, T>(_: &F, _: &T) {}
+
+// `catf!` asks `(&__AlignOf(x)).__default_align()` for the alignment of a placeholder with a width
+// but no alignment. Like `std::fmt`, numbers are right-aligned and everything else is left-aligned.
+// Method lookup tries the numeric impls first, since they take the receiver as it is rather than
+// by another reference, so this is decided by the argument's type where `catf!` is expanded.
+#[doc(hidden)]
+pub struct __AlignOf<'a, T: ?Sized + 'a>(pub &'a T);
+
+#[doc(hidden)]
+pub trait __NumericAlign {
+    fn __default_align(&self) -> Align { Align::Right }
+}
+
+#[doc(hidden)]
+pub trait __OtherAlign {
+    fn __default_align(&self) -> Align { Align::Left }
+}
+
+impl<'a, 'b, T: ?Sized> __OtherAlign for &'b __AlignOf<'a, T> {}
+
+macro_rules! impl_numeric_align {
+    ($($t:ty)*) => ($(
+        impl<'a> __NumericAlign for __AlignOf<'a, $t> {}
+        impl<'a, 'b> __NumericAlign for __AlignOf<'a, &'b $t> {}
+    )*)
+}
+
+impl_numeric_align!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+// Write each of `items`, with `sep` between them and `end` after the last.
+#[doc(hidden)]
+pub fn __join<W, I>(w: &mut W, sep: &[u8], items: I, end: &[u8]) -> std::io::Result<()>
+where W: std::io::Write, I: IntoIterator, I::Item: Show {
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            w.write_all(sep)?;
+        }
+
+        item.write(w)?;
+    }
+
+    w.write_all(end)
+}
+
+// Make room for `additional` more bytes. A buffer that hasn't allocated yet is being built from
+// scratch by `scat!` or similar, so it gets exactly what it needs. Anything else is being appended
+// to, perhaps in a loop, so it grows in the usual amortized way.
+#[doc(hidden)]
+pub fn __reserve(buf: &mut Vec<u8>, additional: usize) {
+    // A length too big to reserve, like one that saturated at `usize::MAX`, can't be right, so
+    // leave the buffer to grow as it's written to.
+    let _ = if buf.capacity() == 0 {
+        buf.try_reserve_exact(additional)
+    } else {
+        buf.try_reserve(additional)
+    };
+}
+
+/// Perform a cat which appends to an initial argument of type `String`.
+///
+/// Room for the new text is reserved up front and only the appended bytes are checked for valid
+/// utf-8, so appending costs time proportional to what's being added rather than to the length of
+/// the whole string.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let mut s = String::from("Meow");
+///
+/// strcat!(s, ',', ' ', "World");
+/// strcat!(s, '!');
+///
+/// assert_eq!(s, "Meow, World!");
+/// # }
+/// ```
+#[macro_export] macro_rules! strcat {
+    // `@ln` is `strcatln!`, which appends a newline after the cat.
+    ($(@$ln:ident)* $str:expr, $($args:tt)*) => ({
+        // Taking the String's buffer doesn't copy anything, and lets us write into it without
+        // unsafe code.
+        let mut v     = ::std::mem::replace(&mut $str, String::new()).into_bytes();
+        let     start = v.len();
+
+        $crate::__expect_written(__cat_bind!(__cat_extend ($(@$ln)* &mut v,) $($args)*),
+                                 "strcat!");
+
+        if ::std::str::from_utf8(&v[start..]).is_err() {
+            panic!("strcat! macro generated invalid utf-8");
+        }
+
+        // The old contents came from a String and the new ones were just checked, so the whole
+        // buffer is valid utf-8.
+        $str = unsafe { String::from_utf8_unchecked(v) };
+    })
+}
+
+/// Like `strcat!`, but appends a newline after the cat.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let mut s = String::new();
+///
+/// strcatln!(s, "Tom: ", 9);
+/// strcatln!(s, "Mittens: ", 3);
+///
+/// assert_eq!(s, "Tom: 9\nMittens: 3\n");
+/// # }
+/// ```
+#[macro_export] macro_rules! strcatln {
+    ($str:expr, $($args:tt)*) => ({
+        strcat!(@ln $str, $($args)*)
+    })
+}
+
+/// Perform a cat which appends to an existing `Vec<u8>`, reserving exactly the space it needs
+/// first. Evaluates to the number of bytes appended.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let mut buf = b"GET ".to_vec();
+///
+/// let n = vcat!(buf, "/index.html", ' ', "HTTP/1.", 1);
+///
+/// assert_eq!(n, 20);
+/// assert_eq!(buf, b"GET /index.html HTTP/1.1");
+/// # }
+/// ```
+#[macro_export] macro_rules! vcat {
+    ($buf:expr, $($args:tt)*) => ({
+        let buf: &mut Vec<u8> = &mut $buf;
+
+        $crate::__expect_written(__cat_bind!(__cat_extend (buf,) $($args)*), "vcat!")
+    })
+}
+
+// Reserve room for a cat in a `Vec<u8>` and then write it there. The room comes from the cat's size
+// hint, so the buffer may still grow while writing if the hint is only a lower bound. Because this
+// takes two passes over the arguments, callers go through `__cat_bind!` so that each one is only
+// evaluated once. The `Vec` never refuses a write, but an argument can fail by itself, as a field
+// whose `Overflow` is `Error` does, so this evaluates to an `io::Result`.
+#[doc(hidden)]
+#[macro_export] macro_rules! __cat_extend {
+    (@ln $buf:expr, $($args:tt)*) => ({
+        let buf: &mut Vec<u8> = $buf;
+
+        $crate::__reserve(buf, cat_size_hint!($($args)*).lower().saturating_add(1));
+
+        cat_write!(buf, $($args)*).map(|n| {
+            buf.push(b'\n');
+
+            n + 1
+        })
+    });
+
+    ($buf:expr, $($args:tt)*) => ({
+        let buf: &mut Vec<u8> = $buf;
+
+        $crate::__reserve(buf, cat_size_hint!($($args)*).lower());
+
+        cat_write!(buf, $($args)*)
+    })
+}
+
+// Bind every format and object in a cat to a local, then invoke `$cb!($($extra)* ...)` with the
+// cat rewritten in terms of those locals. The arguments are taken apart by `__cat_args!`, which
+// reports a malformed one where it was written, and checks each object can be shown or formatted
+// before it's used.
+#[doc(hidden)]
+#[macro_export] macro_rules! __cat_bind {
+    ($cb:ident ($($extra:tt)*) $($args:tt)*) => ({
+        $crate::__cat_args!($crate, $cb ($($extra)*) $($args)*)
+    })
+}
+
+/// Write rows of cats to `$file` as a table, padding each column to the width of its widest cell.
+///
+/// Each row is a bracketed list of cells, and each cell is anything that can appear in a cat,
+/// including a `fmt ; obj` pair. Columns are separated by two spaces unless a `sep = ...` is
+/// given first. Every cell is rendered exactly once, and rows may have different numbers of cells.
+/// Evaluates to an `io::Result<()>`. For headers, borders or wrapping, see `table::Table`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::HEX;
+/// # fn main() {
+/// let mut out = Vec::new();
+///
+/// table!(out, sep = " | ",
+///     ["Name", "Lives", "Id"],
+///     ["Tom", 9, HEX; 255u8],
+///     ["Mittens", 3, HEX; 10u8],
+/// ).unwrap();
+///
+/// assert_eq!(String::from_utf8(out).unwrap(), "\
+/// Name    | Lives | Id
+/// Tom     | 9     | ff
+/// Mittens | 3     | a
+/// ");
+/// # }
+/// ```
+#[macro_export] macro_rules! table {
+    ($file:expr, sep = $sep:expr, $([$($cell:tt)*]),* $(,)*) => ({
+        use ::std::io::Write;
+
+        let mut cells = Vec::new();
+        let mut rows  = Vec::new();
+
+        $({
+            let mut lens = Vec::new();
+
+            __table_row!(cells, lens, $($cell)*);
+            rows.push(lens);
+        })*
+
+        match $file.by_ref() {
+            w => $crate::__write_table(w, $sep, &cells, &rows),
+        }
+    });
+
+    ($file:expr, $($rows:tt)*) => ({
+        table!($file, sep = "  ", $($rows)*)
+    })
+}
+
+#[doc(hidden)]
+#[macro_export] macro_rules! __table_row {
+    ($cells:ident, $lens:ident $(,)*) => (());
+
+    ($cells:ident, $lens:ident, ($fmt:expr ; $obj:expr) $($rest:tt)*) => ({
+        __table_row!($cells, $lens, $fmt ; $obj $($rest)*)
+    });
+
+    ($cells:ident, $lens:ident, $fmt:expr ; $obj:expr) => ({
+        $lens.push(vcat!($cells, $fmt ; $obj));
+    });
+
+    ($cells:ident, $lens:ident, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
+        $lens.push(vcat!($cells, $fmt ; $obj));
+        __table_row!($cells, $lens, $($rest)*)
+    });
+
+    ($cells:ident, $lens:ident, $obj:expr) => ({
+        $lens.push(vcat!($cells, $obj));
+    });
+
+    ($cells:ident, $lens:ident, $obj:expr, $($rest:tt)*) => ({
+        $lens.push(vcat!($cells, $obj));
+        __table_row!($cells, $lens, $($rest)*)
+    })
+}
+
+/// Perform a cat which writes to `$file`, which can be anything that implements tokio's
+/// `AsyncWrite`. The cat is rendered into a buffer straight away, and the macro evaluates to a
+/// `WriteCat` future which writes that buffer and resolves to an `io::Result<()>`. If an argument
+/// fails to render, the future writes nothing and resolves to its error.
+///
+/// Requires the `tokio` feature.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # extern crate tokio;
+/// # fn main() {
+/// let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///
+/// let mut out = Vec::new();
+///
+/// // In an async fn this would be `afcatln!(socket, ...).await?`.
+/// rt.block_on(afcatln!(out, "Meow", ',', ' ', 42)).unwrap();
+///
+/// assert_eq!(out, b"Meow, 42\n");
+/// # }
+/// ```
+#[cfg(feature = "tokio")]
+#[macro_export] macro_rules! afcat {
+    ($file:expr, $($args:tt)*) => ({
+        let mut buffer = Vec::new();
+        let     result = __cat_bind!(__cat_extend (&mut buffer,) $($args)*);
+
+        use $crate::__AsyncByRef;
+
+        $crate::WriteCat::new($file.async_by_ref(), result.map(|_| buffer))
+    })
+}
+
+/// Like `afcat!`, but appends a newline. Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+#[macro_export] macro_rules! afcatln {
+    ($file:expr, $($args:tt)*) => ({
+        let mut buffer = Vec::new();
+        let     result = __cat_bind!(__cat_extend (@ln &mut buffer,) $($args)*);
+
+        use $crate::__AsyncByRef;
+
+        $crate::WriteCat::new($file.async_by_ref(), result.map(|_| buffer))
+    })
+}
+
+/// Like `afcat!`, but writes to tokio's stdout. Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+#[macro_export] macro_rules! acat {
+    ($($args:tt)*) => ({
+        let mut buffer = Vec::new();
+        let     result = __cat_bind!(__cat_extend (&mut buffer,) $($args)*);
+
+        $crate::WriteCat::new($crate::__tokio::io::stdout(), result.map(|_| buffer))
+    })
+}
+
+/// Like `acat!`, but appends a newline. Requires the `tokio` feature.
+#[cfg(feature = "tokio")]
+#[macro_export] macro_rules! acatln {
+    ($($args:tt)*) => ({
+        let mut buffer = Vec::new();
+        let     result = __cat_bind!(__cat_extend (@ln &mut buffer,) $($args)*);
+
+        $crate::WriteCat::new($crate::__tokio::io::stdout(), result.map(|_| buffer))
+    })
+}
+
+/// Concatenate objects into strings using a format string, in the style of `format!`.
+///
+/// Placeholders may be positional (`{}` or `{0}`) or named (`{name}`). A name that isn't given as
+/// an argument is looked up in the surrounding scope. After a ':' a placeholder can specify
+/// `[[fill]align][+][#][0][width][type]`, where `align` is one of '<', '^' or '>' and `type` is
+/// one of 'x', 'X', 'o' or 'b'. These map onto `Pad` and `FormattedInt`. Every argument is
+/// evaluated exactly once.
+///
+/// The format string is checked at compile time, along with the number of arguments and whether
+/// each one can be shown the way its placeholder asks. Those errors point at the offending
+/// argument rather than at the whole macro.
+///
+/// As with `std::fmt`, a placeholder with a width and no alignment right-aligns a number and
+/// left-aligns anything else. That's decided by the argument's type where `catf!` is used, so an
+/// argument of a generic type is left-aligned. The other difference to watch for is that a
+/// negative number given a 'x', 'o' or 'b' type is written as '-' and its magnitude rather than in
+/// two's complement.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let dir = "/tmp";
+/// let n   = 3u32;
+///
+/// assert_eq!(catf!("{} items in {dir}", n, dir = dir), "3 items in /tmp");
+/// assert_eq!(catf!("[{:>6}] {n:#06x}", "id"), "[    id] 0x0003");
+/// assert_eq!(catf!("[{:5}] [{:5}]", n, "id"), format!("[{:5}] [{:5}]", n, "id"));
+/// # }
+/// ```
+#[macro_export] macro_rules! catf {
+    ($($args:tt)*) => ($crate::__catf!($crate, $($args)*))
+}
+
+/// Perform a cat into a `fmt::Write`, most usefully the `fmt::Formatter` passed to a `Display`
+/// impl. Evaluates to a `fmt::Result`.
+///
+/// The formatter's width, fill and other flags are ignored; `impl_display_via_show!` respects
+/// them, at the cost of rendering into a buffer first.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use std::fmt;
+///
+/// struct Cat {
+///     id:   u32,
+///     name: &'static str,
+/// }
+///
+/// impl fmt::Display for Cat {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         cat_to!(f, "id=", self.id, " name=", self.name)
+///     }
+/// }
+///
+/// # fn main() {
+/// let tom = Cat { id: 7, name: "Tom" };
+///
+/// assert_eq!(tom.to_string(), "id=7 name=Tom");
+/// # }
+/// ```
+#[macro_export] macro_rules! cat_to {
+    ($f:expr, $($args:tt)*) => ({
+        let mut w = $crate::FmtWriter::new($f);
+
+        match cat_write!(&mut w, $($args)*) {
+            Ok(_)  => w.finish(),
+            Err(_) => Err(::std::fmt::Error),
+        }
+    })
+}
+
+/// Implement `std::fmt::Display` for one or more types in terms of their `Show` impls, so they
+/// can also be used with `println!`, in error messages, and anywhere else that expects `Display`.
+///
+/// The shown text is passed through `Formatter::pad`, so width, fill and alignment flags work.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use std::io;
+///
+/// use cats::Show;
+///
+/// struct Meters(u32);
+///
+/// impl Show for Meters {
+///     fn len(&self) -> usize {
+///         cat_len!(self.0, 'm')
+///     }
+///
+///     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+///         cat_write!(w, self.0, 'm')
+///     }
+/// }
+///
+/// impl_display_via_show!(Meters);
+///
+/// # fn main() {
+/// assert_eq!(format!("[{:>5}]", Meters(12)), "[  12m]");
+/// # }
+/// ```
+#[macro_export] macro_rules! impl_display_via_show {
+    ($($t:ty),+ $(,)*) => ($(
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let mut buffer = ::std::vec::Vec::with_capacity($crate::Show::len(self));
+
+                // Only the value itself can fail to write, as a field too narrow for it does when
+                // its `Overflow` is `Error`.
+                if $crate::Show::write(self, &mut buffer).is_err() {
+                    return Err(::std::fmt::Error);
+                }
+
+                match ::std::str::from_utf8(&buffer) {
+                    Ok(s)  => f.pad(s),
+                    Err(_) => Err(::std::fmt::Error),
+                }
+            }
+        }
+    )+)
+}
+
+/// Implement `Show` for one or more types in terms of their `std::fmt::Display` impls, the same
+/// way `Disp` does. This is for code moving to cats a piece at a time: a type gets one line here
+/// instead of a `Disp` at every cat it appears in, and a proper `Show` impl can replace the line
+/// later without touching those cats.
+///
+/// Like `Disp`, the length takes a counting pass through `Display`, so the value is formatted
+/// twice per cat.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use std::fmt;
+///
+/// struct Version(u32, u32);
+///
+/// impl fmt::Display for Version {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "v{}.{}", self.0, self.1)
+///     }
+/// }
+///
+/// impl_show_via_display!(Version);
+///
+/// # fn main() {
+/// assert_eq!(scat!("cats ", Version(0, 1)), "cats v0.1");
+/// # }
+/// ```
+#[macro_export] macro_rules! impl_show_via_display {
+    ($($t:ty),+ $(,)*) => ($(
+        impl $crate::Show for $t {
+            fn len(&self) -> usize { $crate::Show::len(&$crate::Disp(self)) }
+            fn size_hint(&self) -> $crate::SizeHint { $crate::Show::size_hint(&$crate::Disp(self)) }
+            fn write<W: ::std::io::Write>(&self, w: &mut W) -> ::std::io::Result<usize> {
+                $crate::Show::write(&$crate::Disp(self), w)
+            }
+        }
+    )+)
+}
+
+/// Concatenate objects into strings.
+///
+/// The result is measured before it's written so that it can be allocated exactly once, but each
+/// argument is still only evaluated once.
+/// 
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let s = scat!("Meow", ',', ' ', String::from("World"));
+///
+/// assert_eq!(s, "Meow, World");
+///
+/// // A trailing comma is fine, as it is with std's macros.
+/// let s = scat!(
+///     "Meow",
+///     42,
+/// );
+///
+/// assert_eq!(s, "Meow42");
+///
+/// // A format and its object can be wrapped in parentheses to set them apart.
+/// let s = scat!("id=", (cats::HEX; 255u8), ' ', (1 + 2));
+///
+/// assert_eq!(s, "id=ff 3");
+/// # }
+/// ```
+///
+/// The arguments are checked when the macro is expanded, and a malformed one is reported where it
+/// was written. So is an object that can't be shown, or can't be formatted by its format. These
+/// go for every macro that takes a cat, not just `scat!`.
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// // A typo for `,`: a literal can't be a format.
+/// let s = scat!("id="; 42);
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate cats;
+/// # use cats::Rep;
+/// # fn main() {
+/// // Not `Rep(2) ; "ab".len()`, which is what this would mean without the parentheses.
+/// let s = scat!((Rep(2); "ab").len());
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// // `HEX` formats integers, not strings.
+/// let s = scat!(cats::HEX; "ff");
+/// # }
+/// ```
+#[macro_export] macro_rules! scat {
+    ($($args:tt)*) => ({
+        // Here we're checking for valid utf-8, maybe this should be unchecked?
+        match try_scat!($($args)*) {
+            Ok(s)                            => s,
+            Err($crate::ScatError::Write(e)) => panic!("scat! failed to write: {}", e),
+            Err($crate::ScatError::Utf8(_))  => panic!("scat! macro generated invalid utf-8"),
+        }
+    })
+}
+
+/// Like `scat!`, but returns a `CatString`, which keeps cats of up to 24 bytes inline instead of
+/// allocating.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let label = scat_small!("lives: ", 9);
+///
+/// assert_eq!(label, "lives: 9");
+/// assert!(label.is_inline());
+/// # }
+/// ```
+#[macro_export] macro_rules! scat_small {
+    ($($args:tt)*) => ({
+        __cat_bind!(__scat_small () $($args)*)
+    })
+}
+
+#[doc(hidden)]
+#[macro_export] macro_rules! __scat_small {
+    ($($args:tt)*) => ({
+        let mut buf = $crate::__CatBuf::new(cat_size_hint!($($args)*).lower());
+
+        $crate::__expect_written(cat_write!(&mut buf, $($args)*), "scat_small!");
+
+        buf.finish()
+    })
+}
+
+/// Perform a cat into a caller-provided `&mut [u8]` without allocating. Evaluates to a
+/// `Result<&str, BufferFull>`, with the `str` borrowed from the front of the buffer. If the cat
+/// doesn't fit, whatever did fit is left in the buffer, which may end partway through an argument
+/// that's written in pieces, such as a padded one. An argument that fails for any other reason,
+/// such as a field whose `Overflow` is `Error`, panics as it would in `scat!`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::BufferFull;
+/// # fn main() {
+/// let mut buf = [0u8; 8];
+///
+/// assert_eq!(scat_into_slice!(&mut buf, "id=", 42), Ok("id=42"));
+/// assert_eq!(scat_into_slice!(&mut buf, "id=", 123456789), Err(BufferFull));
+/// # }
+/// ```
+#[macro_export] macro_rules! scat_into_slice {
+    ($buf:expr, $($args:tt)*) => ({
+        __cat_bind!(__scat_into_slice ($buf,) $($args)*)
+    })
+}
+
+#[doc(hidden)]
+#[macro_export] macro_rules! __scat_into_slice {
+    ($buf:expr, $($args:tt)*) => ({
+        let mut w = $crate::SliceWriter::new($buf);
+
+        match cat_write!(&mut w, $($args)*) {
+            Ok(_)                                     => Ok(w.into_str()),
+            Err(ref e) if $crate::__is_buffer_full(e) => Err($crate::BufferFull),
+            Err(e)                                    => {
+                panic!("scat_into_slice! failed to write: {}", e)
+            },
+        }
+    })
+}
+
+/// Like `scat!`, but appends a newline. Room for it is reserved along with the rest of the cat.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let s = scatln!("Meow", ',', ' ', 42);
+///
+/// assert_eq!(s, "Meow, 42\n");
+/// # }
+/// ```
+#[macro_export] macro_rules! scatln {
+    ($($args:tt)*) => ({
+        let mut buffer = Vec::new();
+
+        $crate::__expect_written(__cat_bind!(__cat_extend (@ln &mut buffer,) $($args)*),
+                                 "scatln!");
+
+        match String::from_utf8(buffer) {
+            Ok(s) => s,
+            _     => panic!("scatln! macro generated invalid utf-8"),
+        }
+    })
+}
+
+/// Perform a cat into a new `Vec<u8>`, with no utf-8 conversion or checking. Along with `Raw`,
+/// this can build frames that mix binary and text. To write the bytes somewhere else instead, use
+/// `vcat!` or `fcat!`, which don't check either.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let v = bcat!("Meow", ',', ' ', 42);
+///
+/// assert_eq!(v, b"Meow, 42");
+/// # }
+/// ```
+#[macro_export] macro_rules! bcat {
+    ($($args:tt)*) => ({
+        let mut buffer = Vec::new();
+
+        $crate::__expect_written(__cat_bind!(__cat_extend (&mut buffer,) $($args)*), "bcat!");
+
+        buffer
+    })
+}
+
+/// Like `scat!`, but returns a `ScatError` instead of panicking if an argument fails to write or
+/// the result isn't valid utf-8.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Pad, Align, Overflow};
+/// # fn main() {
+/// let s = try_scat!("Meow", ',', ' ', 42);
+///
+/// assert_eq!(s.unwrap(), "Meow, 42");
+///
+/// let name = Pad { width: 4, fill: ' ', align: Align::Left, overflow: Overflow::Error };
+///
+/// assert!(try_scat!(name; "Mittens").is_err());
+/// # }
+/// ```
+#[macro_export] macro_rules! try_scat {
+    ($($args:tt)*) => ({
+        let mut buffer = Vec::new();
+
+        match __cat_bind!(__cat_extend (&mut buffer,) $($args)*) {
+            Ok(_)  => String::from_utf8(buffer).map_err($crate::ScatError::Utf8),
+            Err(e) => Err($crate::ScatError::Write(e)),
+        }
+    })
+}
+
+/// Like `scat!`, but skips checking that the result is valid utf-8.
+///
+/// This relies on every `Show` and `Format` impl in the cat keeping its promise to write valid
+/// utf-8, so it has to be called from an `unsafe` block. It's meant for hot loops where the check
+/// shows up in a profile.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let s = unsafe { scat_unchecked!("Meow", ',', ' ', 42) };
+///
+/// assert_eq!(s, "Meow, 42");
+/// # }
+/// ```
+#[macro_export] macro_rules! scat_unchecked {
+    ($($args:tt)*) => ({
+        let mut buffer = Vec::new();
+
+        $crate::__expect_written(__cat_bind!(__cat_extend (&mut buffer,) $($args)*),
+                                 "scat_unchecked!");
+
+        String::from_utf8_unchecked(buffer)
+    })
+}
+
+/// Return the length in bytes that a cat would create.
+///
+/// The lengths of literal arguments are added up at compile time, so only the other arguments cost
+/// anything at runtime. A total too big for a `usize` saturates at `usize::MAX` instead of
+/// wrapping around, and the macros that reserve room from it then leave the buffer to grow.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Rep;
+///
+/// # fn main() {
+///  let len = cat_len!("Meow", ',', ' ', String::from("World"));
+///
+///  assert_eq!(len, "Meow, World".len());
+///  assert_eq!(cat_len!("Meow", Rep(usize::MAX); "World"), usize::MAX);
+/// # }
+/// ```
+#[macro_export] macro_rules! cat_len {
+    ($($args:tt)*) => ({
+        let mut total_len: usize = 0;
+
+        $crate::__cat_check!($crate, produce_len_code (total_len,) $($args)*)
+    })
+}
+
+/// Return the `SizeHint` of a cat: exact if every argument knows its length up front, and a lower
+/// bound otherwise.
+#[macro_export] macro_rules! cat_size_hint {
+    ($($args:tt)*) => ({
+        let mut total_hint = $crate::SizeHint::Exact(0);
+
+        $crate::__cat_check!($crate, produce_hint_code (total_hint,) $($args)*)
+    })
+}
+
+/// Write the arguments to `$buffer`, evaluating to an `io::Result<usize>` with the number of bytes
+/// written.
+///
+/// Literals next to each other, like `"id", '=', 42`, are joined with `concat!` when the macro is
+/// expanded and written with one call.
+///
+/// In debug builds each argument is checked as it's written, and a `Show` or `Format` whose `write`
+/// doesn't put out the bytes its `len` promised is a panic naming the type. Release builds skip
+/// the check.
+///
+/// # Examples
+/// ```should_panic
+/// # #[macro_use] extern crate cats;
+/// # use cats::Show;
+/// # use std::io::{self, Write};
+/// struct Liar;
+///
+/// impl Show for Liar {
+///     fn len(&self) -> usize { 3 }
+///     fn write<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+///         w.write_all(b"meow").map(|()| 4)
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut out = Vec::new();
+///
+/// // panics: `Liar` promised 3 bytes from `len`, wrote 4 and reported 4
+/// cat_write!(out, "cat says ", Liar).unwrap();
+/// # }
+/// ```
+#[macro_export] macro_rules! cat_write {
+    ($buffer:expr, $($args:tt)*) => ({
+        #[allow(unused_imports)]
+        use $crate::{__IoTarget, __FmtTarget};
+
+        // Method syntax reborrows if `$buffer` is already a `&mut W`, and borrows it otherwise. The
+        // match keeps a temporary like `io::stdout()` alive until the writing is done.
+        match $buffer.__cat_target() {
+            mut w => match $crate::__cat_check!($crate, produce_write_code (0, &mut w,) $($args)*) {
+                Ok(n)  => $crate::CatTarget::finish(w).map(|()| n),
+                Err(e) => Err(e),
+            },
+        }
+    })
+}
+
+#[macro_export] macro_rules! produce_len_code {
+    // Literals are set aside in the brackets as they come up, and their lengths are added at the
+    // end as a single constant, so only the other arguments cost anything at runtime.
+    (@lits [$($lits:literal)*] $len:expr,) => ({
+        $($crate::__show_literal(&$lits);)*
+
+        $len = $len.saturating_add(concat!($($lits),*).len());
+
+        $len
+    });
+
+    // A parenthesized `(fmt ; obj)` is unwrapped. This has to come before the rules that parse an
+    // expression, which would choke on the ';'.
+    (@lits [$($lits:literal)*] $len:expr, ($fmt:expr ; $obj:expr) $($rest:tt)*) => ({
+        $crate::produce_len_code!(@lits [$($lits)*] $len, $fmt ; $obj $($rest)*)
+    });
+
+    (@lits [$($lits:literal)*] $len:expr, $fmt:expr ; $obj:expr) => ({
+        $crate::produce_len_code!(@lits [$($lits)*] $len, $fmt ; $obj,)
+    });
+
+    (@lits [$($lits:literal)*] $len:expr, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
+        $len = $len.saturating_add($crate::Format::len(&$fmt, &$obj));
+
+        $crate::produce_len_code!(@lits [$($lits)*] $len, $($rest)*)
+    });
+
+    (@lits [$($lits:literal)*] $len:expr, $lit:literal) => ({
+        $crate::produce_len_code!(@lits [$($lits)* $lit] $len,)
+    });
+
+    (@lits [$($lits:literal)*] $len:expr, $lit:literal, $($rest:tt)*) => ({
+        $crate::produce_len_code!(@lits [$($lits)* $lit] $len, $($rest)*)
+    });
+
+    (@lits [$($lits:literal)*] $len:expr, $obj:expr) => ({
+        $crate::produce_len_code!(@lits [$($lits)*] $len, $obj,)
+    });
+
+    (@lits [$($lits:literal)*] $len:expr, $obj:expr, $($rest:tt)*) => ({
+        $len = $len.saturating_add($crate::Show::len(&$obj));
+
+        $crate::produce_len_code!(@lits [$($lits)*] $len, $($rest)*)
+    });
+
+    ($len:expr, $($args:tt)*) => ({
+        $crate::produce_len_code!(@lits [] $len, $($args)*)
+    })
+}
+
+#[doc(hidden)]
+#[macro_export] macro_rules! produce_hint_code {
+    // Literals are set aside and added as one constant at the end, as in `produce_len_code!`.
+    (@lits [$($lits:literal)*] $hint:expr,) => ({
+        $($crate::__show_literal(&$lits);)*
+
+        $hint = $hint + $crate::SizeHint::Exact(concat!($($lits),*).len());
+
+        $hint
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, ($fmt:expr ; $obj:expr) $($rest:tt)*) => ({
+        $crate::produce_hint_code!(@lits [$($lits)*] $hint, $fmt ; $obj $($rest)*)
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, $fmt:expr ; $obj:expr) => ({
+        $crate::produce_hint_code!(@lits [$($lits)*] $hint, $fmt ; $obj,)
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
+        $hint = $hint + $crate::Format::size_hint(&$fmt, &$obj);
+
+        $crate::produce_hint_code!(@lits [$($lits)*] $hint, $($rest)*)
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, $lit:literal) => ({
+        $crate::produce_hint_code!(@lits [$($lits)* $lit] $hint,)
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, $lit:literal, $($rest:tt)*) => ({
+        $crate::produce_hint_code!(@lits [$($lits)* $lit] $hint, $($rest)*)
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, $obj:expr) => ({
+        $crate::produce_hint_code!(@lits [$($lits)*] $hint, $obj,)
+    });
+
+    (@lits [$($lits:literal)*] $hint:expr, $obj:expr, $($rest:tt)*) => ({
+        $hint = $hint + $crate::Show::size_hint(&$obj);
+
+        $crate::produce_hint_code!(@lits [$($lits)*] $hint, $($rest)*)
+    });
+
+    ($hint:expr, $($args:tt)*) => ({
+        $crate::produce_hint_code!(@lits [] $hint, $($args)*)
+    })
+}
+
+#[macro_export] macro_rules! produce_write_code {
+    // A run of literals is collected in the brackets, then joined with `concat!` and written in one
+    // go, skipping the debug check. Each one is still checked for a `Show` impl, so a run accepts
+    // just what a single literal would.
+    (@lits [$($lits:literal)*] $written:expr, $w:expr, $lit:literal) => ({
+        $crate::produce_write_code!(@lits [$($lits)* $lit] $written, $w,)
+    });
 
-pub use traits::{Show, Format, SignPolicy, Utf8Write, FormattedInt, Rep, HEX};
+    (@lits [$($lits:literal)*] $written:expr, $w:expr, $lit:literal, $($rest:tt)*) => ({
+        $crate::produce_write_code!(@lits [$($lits)* $lit] $written, $w, $($rest)*)
+    });
 
+    (@lits [$($lits:literal)*] $written:expr, $w:expr, $($rest:tt)*) => ({
+        $($crate::__show_literal(&$lits);)*
 
-/// Perform a cat which appends to an initial argument of type `String`.
-#[macro_export] macro_rules! strcat {
+        match $crate::Show::write(concat!($($lits),*), $w) {
+            Ok(n)  => $crate::produce_write_code!(n + $written, $w, $($rest)*),
+            Err(e) => Err(e),
+        }
+    });
 
-    ($str:expr, $($args:tt)*) => ({
-        use ::std::mem;
+    ($written:expr, $w:expr,) => ({
+        Ok::<usize, ::std::io::Error>($written)
+    });
 
-        let mut v = mem::replace(&mut $str, String::new()).into_bytes();
+    ($written:expr, $w:expr, ($fmt:expr ; $obj:expr) $($rest:tt)*) => ({
+        $crate::produce_write_code!($written, $w, $fmt ; $obj $($rest)*)
+    });
 
-        fcat!(v, $($args)*).ok();
+    ($written:expr, $w:expr, $fmt:expr ; $obj:expr) => ({
+        match $crate::__write_format(&$fmt, &$obj, $w) {
+            Ok(n)  => Ok(n + $written),
+            Err(e) => Err(e),
+        }
+    });
 
-        // Like with scat!, I'm not sure this should be the checked version. If we're appending to a
-        // String this is going to make the complexity skyrocket.
-        let s = match String::from_utf8(v) {
-            Ok(s) => s,
-            _     => panic!("strcat! macro generated invalid utf-8"),
-        };
+    ($written:expr, $w:expr, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
+        match $crate::__write_format(&$fmt, &$obj, $w) {
+            Ok(n)  => $crate::produce_write_code!(n + $written, $w, $($rest)*),
+            Err(e) => Err(e),
+        }
+    });
+
+    ($written:expr, $w:expr, $lit:literal) => ({
+        $crate::produce_write_code!(@lits [$lit] $written, $w,)
+    });
+
+    ($written:expr, $w:expr, $lit:literal, $($rest:tt)*) => ({
+        $crate::produce_write_code!(@lits [$lit] $written, $w, $($rest)*)
+    });
+
+    ($written:expr, $w:expr, $obj:expr) => ({
+        match $crate::__write_show(&$obj, $w) {
+            Ok(n)  => Ok(n + $written),
+            Err(e) => Err(e),
+        }
+    });
 
-        mem::replace(&mut $str, s);
+    ($written:expr, $w:expr, $obj:expr, $($rest:tt)*) => ({
+        match $crate::__write_show(&$obj, $w) {
+            Ok(n)  => $crate::produce_write_code!(n + $written, $w, $($rest)*),
+            Err(e) => Err(e),
+        }
     })
 }
 
-/// Concatenate objects into strings.
-/// 
+/// Perform a cat which writes each argument straight into `$file`, which can be anything that
+/// implements `io::Write` or `fmt::Write` (see `CatTarget`). No intermediate string is built, but
+/// small arguments are gathered in a buffer on the stack and written together, so that a cat of
+/// many short pieces to an unbuffered `File` doesn't turn into a write for each one.
+///
 /// # Examples
 /// ```
 /// # #[macro_use] extern crate cats;
 /// # fn main() {
-/// let s = scat!("Meow", ',', ' ', String::from("World"));
+/// let mut out = Vec::new();
 ///
-/// assert_eq!(s, "Meow, World");
+/// fcat!(out, "Meow", ',', ' ', 42).unwrap();
+///
+/// assert_eq!(out, b"Meow, 42");
 /// # }
 /// ```
-#[macro_export] macro_rules! scat {
-    ($($args:tt)*) => ({
-        let len = cat_len!($($args)*);
+#[macro_export] macro_rules! fcat {
+    ($file:expr, $($args:tt)*) => ({
+        #[allow(unused_imports)]
+        use $crate::{__IoTarget, __FmtTarget};
 
-        let mut buffer = Vec::with_capacity(len);
+        match $file.__cat_target() {
+            mut w => {
+                let mut batch = $crate::__Batch::new(&mut w);
 
-        cat_write!(&mut buffer, $($args)*).unwrap();
+                match $crate::__cat_check!($crate, produce_write_code (0, &mut batch,) $($args)*) {
+                    Ok(_)  => batch.finish().and_then(|()| $crate::CatTarget::finish(w)),
+                    Err(e) => Err(e),
+                }
+            },
+        }
+    })
+}
 
-        // Here we're checking for valid utf-8, maybe this should be unchecked?
-        match String::from_utf8(buffer) {
-            Ok(s) => s,
-            _     => panic!("scat! macro generated invalid utf-8"),
+#[macro_export] macro_rules! fcatln {
+    ($file:expr) => ({
+        fcatln!($file,)
+    });
+
+    ($file:expr, $($args:tt)*) => ({
+        #[allow(unused_imports)]
+        use $crate::{__IoTarget, __FmtTarget};
+
+        // The newline is written separately, since appending it to `$($args)*` would leave two
+        // commas in a row if the caller used a trailing comma.
+        match $file.__cat_target() {
+            mut w => {
+                let mut batch = $crate::__Batch::new(&mut w);
+
+                match $crate::__cat_check!($crate, produce_write_code (0, &mut batch,) $($args)*)
+                    .and_then(|_| $crate::produce_write_code!(0, &mut batch, '\n')) {
+                    Ok(_)  => batch.finish().and_then(|()| $crate::CatTarget::finish(w)),
+                    Err(e) => Err(e),
+                }
+            },
         }
     })
 }
 
-/// Return the length in bytes that a cat would create.
+/// Like `fcat!`, but hands the whole cat to `$file` with `write_vectored`, for cats of a few large
+/// strings going to a file or socket. Strings, `Raw`, and anything else whose `stored_bytes` has
+/// them to hand are passed along where they are, without being copied. Everything else is
+/// rendered into one scratch buffer first. `$file` has to implement `io::Write`.
 ///
 /// # Examples
 /// ```
 /// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let header = String::from("HTTP/1.1 200 OK\r\n");
+/// let body   = "x".repeat(4096);
+/// let mut out = Vec::new();
+///
+/// fcat_vectored!(out, header, "Content-Length: ", body.len(), "\r\n\r\n", body).unwrap();
+///
+/// assert_eq!(out.len(), 17 + 24 + 4096);
+/// # }
+/// ```
+#[macro_export] macro_rules! fcat_vectored {
+    ($file:expr, $($args:tt)*) => ({
+        __cat_bind!(__gather_code ($file,) $($args)*).map(|_| ())
+    })
+}
+
+#[doc(hidden)]
+#[macro_export] macro_rules! __gather_code {
+    (@g $g:ident,) => (());
+
+    (@g $g:ident, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
+        $g.format(&$fmt, &$obj);
+        __gather_code!(@g $g, $($rest)*)
+    });
+
+    (@g $g:ident, $lit:literal, $($rest:tt)*) => ({
+        $crate::__show_literal(&$lit);
+        $g.show(concat!($lit));
+        __gather_code!(@g $g, $($rest)*)
+    });
+
+    (@g $g:ident, $obj:expr, $($rest:tt)*) => ({
+        $g.show(&$obj);
+        __gather_code!(@g $g, $($rest)*)
+    });
+
+    ($file:expr, $($segs:tt)*) => ({
+        let mut g = $crate::__Gather::new();
+
+        __gather_code!(@g g, $($segs)*);
+
+        g.write_to(&mut $file)
+    })
+}
+
+#[macro_export] macro_rules! ecat {
+    ($($args:tt)*) => ({
+        // Hold the lock for the whole cat so output from other threads can't land in the middle.
+        fcat!($crate::__stderr(), $($args)*).unwrap()
+    })
+}
+
+#[macro_export] macro_rules! ecatln {
+    ($($args:tt)*) => ({
+        fcatln!($crate::__stderr(), $($args)*).unwrap()
+    })
+}
+
+/// Panic with a message built by `scat!`.
 ///
+/// # Examples
+/// ```should_panic
+/// # #[macro_use] extern crate cats;
 /// # fn main() {
-///  let len = cat_len!("Meow", ',', ' ', String::from("World"));
+/// let id = 7;
 ///
-///  assert_eq!(len, "Meow, World".len());
+/// pcat!("no cat with id ", id);
 /// # }
 /// ```
-#[macro_export] macro_rules! cat_len {
+#[macro_export] macro_rules! pcat {
     ($($args:tt)*) => ({
-        let mut total_len = 0;
+        panic!("{}", scat!($($args)*))
+    })
+}
+
+/// Like `assert!`, but with a failure message built by `scat!`. The message arguments are only
+/// evaluated if the assertion fails.
+///
+/// # Examples
+/// ```should_panic
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let lives = 10;
+///
+/// assert_cat!(lives <= 9, "too many lives: ", lives);
+/// # }
+/// ```
+#[macro_export] macro_rules! assert_cat {
+    ($cond:expr $(,)*) => ({
+        if !$cond {
+            panic!("assertion failed: {}", stringify!($cond))
+        }
+    });
 
-        produce_len_code!(total_len, $($args)*)
+    ($cond:expr, $($args:tt)*) => ({
+        if !$cond {
+            panic!("assertion failed: {}: {}", stringify!($cond), scat!($($args)*))
+        }
     })
 }
 
-/// Write the 
-#[macro_export] macro_rules! cat_write {
-    ($buffer:expr, $($args:tt)*) => ({
-        produce_write_code!(0, $buffer, $($args)*)
+/// Return early with an error built by `scat!` if `$cond` is false. The `String` is converted
+/// with `From`, so this works in any function whose error type can be made from one. The message
+/// arguments are only evaluated if the condition fails.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// fn feed(bowls: u32) -> Result<(), String> {
+///     ensure_cat!(bowls > 0, "can't feed from ", bowls, " bowls");
+///
+///     Ok(())
+/// }
+///
+/// # fn main() {
+/// assert_eq!(feed(0), Err(String::from("can't feed from 0 bowls")));
+/// # }
+/// ```
+#[macro_export] macro_rules! ensure_cat {
+    ($cond:expr, $($args:tt)*) => ({
+        if !$cond {
+            return ::std::result::Result::Err(::std::convert::From::from(scat!($($args)*)));
+        }
     })
 }
 
-#[macro_export] macro_rules! produce_len_code {
-    ($len:expr, $fmt:expr ; $obj:expr) => ({
-        $len += $crate::Format::len(&$fmt, &$obj);
+/// Print `file:line: expr = value` to stderr and return the value, like `std::dbg!` but showing
+/// the value with `Show`. Several expressions can be given at once, in which case they're returned
+/// as a tuple.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let x = dcat!(6 * 7);
+///
+/// assert_eq!(x, 42);
+/// # }
+/// ```
+#[macro_export] macro_rules! dcat {
+    () => ({
+        ecatln!(file!(), ':', line!())
+    });
 
-        $len
+    ($val:expr) => ({
+        // A match, rather than a let, keeps temporaries in `$val` alive for the whole expression.
+        match $val {
+            tmp => {
+                ecatln!(file!(), ':', line!(), ": ", stringify!($val), " = ", tmp);
+                tmp
+            }
+        }
     });
 
-    ($len:expr, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
-        $len += $crate::Format::len(&$fmt, &$obj);
+    ($val:expr,) => (dcat!($val));
 
-        produce_len_code!($len, $($rest)*)
+    ($($val:expr),+ $(,)*) => (($(dcat!($val)),+,))
+}
+
+#[cfg(feature = "log")]
+#[doc(hidden)]
+#[macro_export] macro_rules! __logcat {
+    ($level:expr, $($args:tt)*) => ({
+        let level = $level;
+
+        // Only build the message if a logger is going to look at it.
+        if $crate::__log::log_enabled!(level) {
+            let mut scratch = $crate::__Scratch::take();
+
+            $crate::__expect_written(__cat_bind!(__cat_extend (scratch.buf(),) $($args)*),
+                                     "the log cat");
+
+            $crate::__log::log!(level, "{}", scratch.as_str());
+        }
+    })
+}
+
+/// Log a cat at `Trace` level through the `log` crate. Requires the `log` feature.
+#[cfg(feature = "log")]
+#[macro_export] macro_rules! tracecat {
+    ($($args:tt)*) => (__logcat!($crate::__log::Level::Trace, $($args)*))
+}
+
+/// Log a cat at `Debug` level through the `log` crate. Requires the `log` feature.
+#[cfg(feature = "log")]
+#[macro_export] macro_rules! debugcat {
+    ($($args:tt)*) => (__logcat!($crate::__log::Level::Debug, $($args)*))
+}
+
+/// Log a cat at `Info` level through the `log` crate. Requires the `log` feature.
+///
+/// The message is only built when the level is enabled, and then into a buffer kept by the thread
+/// and reused from one message to the next, so logging in a loop doesn't allocate.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let user = "mittens";
+///
+/// infocat!("logged in: ", user);
+/// # }
+/// ```
+#[cfg(feature = "log")]
+#[macro_export] macro_rules! infocat {
+    ($($args:tt)*) => (__logcat!($crate::__log::Level::Info, $($args)*))
+}
+
+/// Log a cat at `Warn` level through the `log` crate. Requires the `log` feature.
+#[cfg(feature = "log")]
+#[macro_export] macro_rules! warncat {
+    ($($args:tt)*) => (__logcat!($crate::__log::Level::Warn, $($args)*))
+}
+
+/// Log a cat at `Error` level through the `log` crate. Requires the `log` feature.
+#[cfg(feature = "log")]
+#[macro_export] macro_rules! errorcat {
+    ($($args:tt)*) => (__logcat!($crate::__log::Level::Error, $($args)*))
+}
+
+#[macro_export] macro_rules! cat {
+    ($($args:tt)*) => ({
+        try_cat!($($args)*).unwrap()
+    })
+}
+
+#[macro_export] macro_rules! catln {
+    ($($args:tt)*) => ({
+        try_catln!($($args)*).unwrap()
+    })
+}
+
+/// Cat each item of an iterator to stdout, with a separator between them. The separator can be
+/// any list of cat arguments wrapped in square brackets, or just one argument on its own. It's
+/// rendered once up front, so nothing is allocated per item.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let lives = [9, 7, 3];
+///
+/// jcat!(", ", &lives);
+/// jcatln!([' ', '|', ' '], vec!["Tom", "Mittens"]);
+/// # }
+/// ```
+#[macro_export] macro_rules! jcat {
+    ([$($sep:tt)*], $iter:expr) => ({
+        let items = $iter;
+        let sep   = bcat!($($sep)*);
+
+        $crate::__join(&mut $crate::__stdout(), &sep, items, b"").unwrap()
     });
 
-    ($len:expr, $obj:expr) => ({
-        $len += $crate::Show::len(&$obj);
+    ($sep:expr, $iter:expr) => ({
+        jcat!([$sep], $iter)
+    })
+}
+
+/// Like `jcat!`, but appends a newline.
+#[macro_export] macro_rules! jcatln {
+    ([$($sep:tt)*], $iter:expr) => ({
+        let items = $iter;
+        let sep   = bcat!($($sep)*);
 
-        $len
+        $crate::__join(&mut $crate::__stdout(), &sep, items, b"\n").unwrap()
     });
 
-    ($len:expr, $obj:expr, $($rest:tt)*) => ({
-        $len += $crate::Show::len(&$obj);
+    ($sep:expr, $iter:expr) => ({
+        jcatln!([$sep], $iter)
+    })
+}
 
-        produce_len_code!($len, $($rest)*)
+/// Like `cat!`, but indented by `$level` levels on top of the depth set with `push_indent`. Each
+/// level is four spaces.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// icatln!(0, "cat {");
+/// cats::push_indent();
+/// icatln!(0, "lives: ", 9);
+/// icatln!(1, "(not many left)");
+/// cats::pop_indent();
+/// icatln!(0, "}");
+/// # }
+/// ```
+#[macro_export] macro_rules! icat {
+    ($level:expr, $($args:tt)*) => ({
+        cat!($crate::Indent($level), $($args)*)
     })
 }
 
-#[macro_export] macro_rules! produce_write_code {
-    ($written:expr, $w:expr, $fmt:expr ; $obj:expr) => ({
-        match $crate::Format::write(&$fmt, &$obj, $w) {
-            Ok(n)  => Ok(n + $written),
-            Err(e) => Err(e),
+/// Like `icat!`, but appends a newline.
+#[macro_export] macro_rules! icatln {
+    ($level:expr, $($args:tt)*) => ({
+        catln!($crate::Indent($level), $($args)*)
+    })
+}
+
+/// Write out anything `cat!` and `catln!` have held back since `set_buffered(true)`, and flush
+/// stdout.
+#[macro_export] macro_rules! catflush {
+    () => ({
+        $crate::__flush_stdout().unwrap()
+    })
+}
+
+/// Like `cat!`, but returns a `Result<(), CatError>` instead of panicking if writing to stdout
+/// fails. The `CatError` says which argument failed.
+#[macro_export] macro_rules! try_cat {
+    ($($args:tt)*) => ({
+        // Hold the lock for the whole cat so output from other threads can't land in the middle.
+        match $crate::__stdout() {
+            mut w => $crate::__cat_check!($crate, __try_write_code (0, 0, &mut w,) $($args)*)
+                .map(|_| ()),
         }
+    })
+}
+
+// Like `produce_write_code!`, but counts arguments as it goes so that a failure can be reported as
+// a `CatError`. A leading `@ln` writes a newline after the last argument, as one more segment.
+#[doc(hidden)]
+#[macro_export] macro_rules! __try_write_code {
+    (@ln $seg:expr, $written:expr, $w:expr,) => ({
+        $crate::__try_write_code!($seg, $written, $w, '\n',)
     });
 
-    ($written:expr, $w:expr, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
+    ($seg:expr, $written:expr, $w:expr,) => ({
+        Ok::<usize, $crate::CatError>($written)
+    });
+
+    ($(@$ln:ident)* $seg:expr, $written:expr, $w:expr, ($fmt:expr ; $obj:expr) $($rest:tt)*) => ({
+        $crate::__try_write_code!($(@$ln)* $seg, $written, $w, $fmt ; $obj $($rest)*)
+    });
+
+    ($(@$ln:ident)* $seg:expr, $written:expr, $w:expr, $fmt:expr ; $obj:expr) => ({
+        $crate::__try_write_code!($(@$ln)* $seg, $written, $w, $fmt ; $obj,)
+    });
+
+    ($(@$ln:ident)* $seg:expr, $written:expr, $w:expr, $fmt:expr ; $obj:expr, $($rest:tt)*) => ({
         match $crate::Format::write(&$fmt, &$obj, $w) {
-            Ok(n)  => produce_write_code!(n + $written, $w, $($rest)*),
-            Err(e) => Err(e),
+            Ok(n)      => $crate::__try_write_code!($(@$ln)* $seg + 1, $written + n, $w, $($rest)*),
+            Err(error) => Err($crate::CatError { segment: $seg, written: $written, error }),
         }
     });
 
-    ($written:expr, $w:expr, $obj:expr) => ({
-        match $crate::Show::write(&$obj, $w) {
-            Ok(n)  => Ok(n + $written),
-            Err(e) => Err(e),
+    ($(@$ln:ident)* $seg:expr, $written:expr, $w:expr, $obj:expr) => ({
+        $crate::__try_write_code!($(@$ln)* $seg, $written, $w, $obj,)
+    });
+
+    ($(@$ln:ident)* $seg:expr, $written:expr, $w:expr, $lit:literal, $($rest:tt)*) => ({
+        $crate::__show_literal(&$lit);
+
+        match $crate::Show::write(concat!($lit), $w) {
+            Ok(n)      => $crate::__try_write_code!($(@$ln)* $seg + 1, $written + n, $w, $($rest)*),
+            Err(error) => Err($crate::CatError { segment: $seg, written: $written, error }),
         }
     });
 
-    ($written:expr, $w:expr, $obj:expr, $($rest:tt)*) => ({
+    ($(@$ln:ident)* $seg:expr, $written:expr, $w:expr, $obj:expr, $($rest:tt)*) => ({
         match $crate::Show::write(&$obj, $w) {
-            Ok(n)  => produce_write_code!(n + $written, $w, $($rest)*),
-            Err(e) => Err(e),
+            Ok(n)      => $crate::__try_write_code!($(@$ln)* $seg + 1, $written + n, $w, $($rest)*),
+            Err(error) => Err($crate::CatError { segment: $seg, written: $written, error }),
         }
     })
 }
 
-#[macro_export] macro_rules! fcat {
-    ($file:expr, $($args:tt)*) => ({
+/// Cat a prompt to stdout, flush it, and read a line from stdin. Evaluates to an
+/// `io::Result<String>` holding the line with surrounding whitespace trimmed, or an
+/// `UnexpectedEof` error if stdin has already been closed.
+///
+/// # Examples
+/// ```no_run
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let name = prompt!("Name of cat ", 1, ": ").unwrap();
+///
+/// catln!("Hello, ", name);
+/// # }
+/// ```
+#[macro_export] macro_rules! prompt {
+    ($($args:tt)*) => ({
         use ::std::io::Write;
-        $file.write_all(scat!($($args)*).as_bytes())
-    })
-}
 
-#[macro_export] macro_rules! fcatln {
-    ($file:expr, $($args:tt)*) => ({
-        fcat!($file, $($args)*, '\n')
-    })
-}
+        let mut out = $crate::__stdout();
 
-#[macro_export] macro_rules! ecat {
-    ($($args:tt)*) => ({
-        fcat!(::std::io::stderr(), $($args)*).unwrap()
-    })
-}
+        match fcat!(out, $($args)*).and_then(|()| out.flush()) {
+            Ok(()) => {
+                let mut line = String::new();
 
-#[macro_export] macro_rules! ecatln {
-    ($($args:tt)*) => ({
-        ecat!($($args)*, '\n')
+                match ::std::io::stdin().read_line(&mut line) {
+                    Ok(0)  => Err(::std::io::Error::new(::std::io::ErrorKind::UnexpectedEof,
+                                                        "stdin closed before a line was read")),
+                    Ok(_)  => Ok(String::from(line.trim())),
+                    Err(e) => Err(e),
+                }
+            },
+            Err(e) => Err(e),
+        }
     })
 }
 
-#[macro_export] macro_rules! cat {
-    ($($args:tt)*) => ({
-        fcat!(::std::io::stdout(),$($args)*).unwrap()
+/// Like `cat!`, but writes through a lock that the caller already holds, such as an
+/// `io::StdoutLock`. This keeps several cats together without other threads' output in between.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let stdout = std::io::stdout();
+/// let mut lock = stdout.lock();
+///
+/// cat_locked!(lock, "Meow", ',', ' ');
+/// cat_locked!(lock, "World", '\n');
+/// # }
+/// ```
+#[macro_export] macro_rules! cat_locked {
+    ($lock:expr, $($args:tt)*) => ({
+        fcat!($lock, $($args)*).unwrap()
     })
 }
 
-#[macro_export] macro_rules! catln {
+/// Like `catln!`, but returns a `Result<(), CatError>` instead of panicking if writing to stdout
+/// fails. The newline counts as one more argument.
+#[macro_export] macro_rules! try_catln {
     ($($args:tt)*) => ({
-        cat!($($args)*, '\n')
+        match $crate::__stdout() {
+            mut w => $crate::__cat_check!($crate, __try_write_code (@ln 0, 0, &mut w,) $($args)*)
+                .map(|_| ()),
+        }
     })
 }
@@ -0,0 +1,349 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::io::{self, Write};
+use std::mem;
+
+use traits::{Show, Format, FormattedInt, SizeHint, DECIMAL_DIGITS, HEX, measure, write_counted};
+
+/// Format floating point numbers so that their decimal points line up in a column.
+///
+/// The integer part (including any sign) is right-aligned in `int_width` characters and the
+/// fraction is rounded to exactly `frac_width` digits. A `frac_width` of zero drops the decimal
+/// point altogether.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::DecimalAlign;
+/// # fn main() {
+/// let col = DecimalAlign { int_width: 4, frac_width: 2 };
+///
+/// assert_eq!(scat!(col; 3.14159), "   3.14");
+/// assert_eq!(scat!(col; -12.5),   " -12.50");
+/// assert_eq!(scat!(col; 100.0),   " 100.00");
+/// # }
+/// ```
+pub struct DecimalAlign {
+    pub int_width:  usize,
+    pub frac_width: usize,
+}
+
+impl DecimalAlign {
+    fn total_width(&self) -> usize {
+        match self.frac_width {
+            0 => self.int_width,
+            n => self.int_width + 1 + n,
+        }
+    }
+}
+
+impl Format<f64> for DecimalAlign {
+    fn len(&self, x: &f64) -> usize {
+        measure(|w| Format::write(self, x, w))
+    }
+
+    // The output is padded to the column width, and is only longer for very large numbers.
+    fn size_hint(&self, _: &f64) -> SizeHint { SizeHint::AtLeast(self.total_width()) }
+
+    fn write<W: io::Write>(&self, x: &f64, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| {
+            write!(w, "{:>width$.prec$}", x, width = self.total_width(), prec = self.frac_width)
+        })
+    }
+}
+
+impl Format<f32> for DecimalAlign {
+    fn len(&self, x: &f32) -> usize { Format::len(self, &(*x as f64)) }
+    fn size_hint(&self, x: &f32) -> SizeHint { Format::size_hint(self, &(*x as f64)) }
+    fn write<W: io::Write>(&self, x: &f32, w: &mut W) -> io::Result<usize> {
+        Format::write(self, &(*x as f64), w)
+    }
+}
+
+/// Format a count together with the singular or plural form of a noun, as in "1 file" or
+/// "3 files". With `show_count` turned off only the noun is written.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Plural;
+/// # fn main() {
+/// let files = Plural { singular: "file", plural: "files", show_count: true };
+///
+/// assert_eq!(scat!(files; 1u32), "1 file");
+/// assert_eq!(scat!(files; 3u32), "3 files");
+/// # }
+/// ```
+pub struct Plural<'x> {
+    pub singular:   &'x str,
+    pub plural:     &'x str,
+    pub show_count: bool,
+}
+
+impl<'x> Plural<'x> {
+    fn noun(&self, one: bool) -> &'x str {
+        if one { self.singular } else { self.plural }
+    }
+
+    fn len_of<T: Show>(&self, n: &T, one: bool) -> usize {
+        let noun = self.noun(one);
+
+        if self.show_count { Show::len(n) + 1 + noun.len() } else { noun.len() }
+    }
+
+    fn write_of<T: Show, W: io::Write>(&self, n: &T, one: bool, w: &mut W) -> io::Result<usize> {
+        let noun = self.noun(one);
+
+        if self.show_count {
+            Ok(Show::write(n, w)? + Show::write(&' ', w)? + Show::write(noun, w)?)
+        } else {
+            Show::write(noun, w)
+        }
+    }
+}
+
+macro_rules! impl_plural {
+    ($($t:ty),*) => ($(
+        impl<'x> Format<$t> for Plural<'x> {
+            fn len(&self, n: &$t) -> usize { self.len_of(n, *n == 1) }
+            fn write<W: io::Write>(&self, n: &$t, w: &mut W) -> io::Result<usize> {
+                self.write_of(n, *n == 1, w)
+            }
+        }
+    )*)
+}
+
+impl_plural!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Format an integer holding a value scaled by `10^scale`, putting the decimal point `scale`
+/// digits from the right. This avoids floating point rounding for things like amounts of money
+/// stored in cents.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::FixedPoint;
+/// # fn main() {
+/// let cents = FixedPoint { scale: 2 };
+///
+/// assert_eq!(scat!(cents; 1234i64), "12.34");
+/// assert_eq!(scat!(cents; -5i64),   "-0.05");
+/// # }
+/// ```
+pub struct FixedPoint {
+    pub scale: u32,
+}
+
+impl FixedPoint {
+    // Split `x` into its integer and fractional parts.
+    fn split(&self, x: u64) -> (u64, u64) {
+        match 10u64.checked_pow(self.scale) {
+            Some(d) => (x / d, x % d),
+            None    => (0, x),
+        }
+    }
+
+    fn fraction(&self) -> FormattedInt<'static> {
+        FormattedInt {
+            digits:  DECIMAL_DIGITS,
+            min_len: self.scale as usize,
+            .. HEX
+        }
+    }
+}
+
+impl Format<u64> for FixedPoint {
+    fn len(&self, x: &u64) -> usize {
+        let (int, frac) = self.split(*x);
+
+        match self.scale {
+            0 => Show::len(x),
+            _ => Show::len(&int) + 1 + Format::len(&self.fraction(), &frac),
+        }
+    }
+
+    fn write<W: io::Write>(&self, x: &u64, w: &mut W) -> io::Result<usize> {
+        let (int, frac) = self.split(*x);
+
+        match self.scale {
+            0 => Show::write(x, w),
+            _ => Ok(Show::write(&int, w)? + Show::write(&'.', w)? +
+                    Format::write(&self.fraction(), &frac, w)?),
+        }
+    }
+}
+
+impl Format<i64> for FixedPoint {
+    fn len(&self, x: &i64) -> usize {
+        if *x < 0 {
+            1 + Format::len(self, &(x.wrapping_neg() as u64))
+        } else {
+            Format::len(self, &(*x as u64))
+        }
+    }
+
+    fn write<W: io::Write>(&self, x: &i64, w: &mut W) -> io::Result<usize> {
+        if *x < 0 {
+            Ok(Show::write(&'-', w)? +
+                Format::write(self, &(x.wrapping_neg() as u64), w)?)
+        } else {
+            Format::write(self, &(*x as u64), w)
+        }
+    }
+}
+
+/// Format an unsigned integer as lowercase hex, zero-padded to the natural width of its type:
+/// two digits for a `u8`, four for a `u16`, and so on. Handy for register and address dumps.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::HexWidth;
+/// # fn main() {
+/// assert_eq!(scat!(HexWidth; 0x1fu8),  "1f");
+/// assert_eq!(scat!(HexWidth; 0x1fu32), "0000001f");
+/// # }
+/// ```
+pub struct HexWidth;
+
+impl HexWidth {
+    fn of<T>() -> FormattedInt<'static> {
+        FormattedInt {
+            min_len: 2 * mem::size_of::<T>(),
+            .. HEX
+        }
+    }
+}
+
+macro_rules! impl_hex_width {
+    ($($t:ty),*) => ($(
+        impl Format<$t> for HexWidth {
+            fn len(&self, x: &$t) -> usize { Format::len(&HexWidth::of::<$t>(), x) }
+            fn write<W: io::Write>(&self, x: &$t, w: &mut W) -> io::Result<usize> {
+                Format::write(&HexWidth::of::<$t>(), x, w)
+            }
+        }
+    )*)
+}
+
+impl_hex_width!(u8, u16, u32, u64, u128, usize);
+
+/// Format a `(numerator, denominator)` pair as a fraction, as in "3/4".
+///
+/// With `reduce` set the fraction is first put in lowest terms, and with `mixed` set any whole
+/// part is split out, as in "1 1/2". A mixed fraction with no remainder is written as just the
+/// whole number.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Fraction;
+/// # fn main() {
+/// let frac = Fraction { mixed: true, reduce: true };
+///
+/// assert_eq!(scat!(frac; (6, 4)),  "1 1/2");
+/// assert_eq!(scat!(frac; (-3, 4)), "-3/4");
+/// assert_eq!(scat!(frac; (8, 4)),  "2");
+/// # }
+/// ```
+pub struct Fraction {
+    pub mixed:  bool,
+    pub reduce: bool,
+}
+
+fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        let t = a % b;
+        a = b;
+        b = t;
+    }
+
+    a
+}
+
+impl Fraction {
+    // Break a fraction into its sign, whole part and proper fraction, any of which may be absent.
+    fn parts(&self, x: &(i64, u64)) -> (bool, Option<u64>, Option<(u64, u64)>) {
+        let neg     = x.0 < 0;
+        let mut num = if neg { x.0.wrapping_neg() as u64 } else { x.0 as u64 };
+        let mut den = x.1;
+
+        if self.reduce && den != 0 {
+            let g = gcd(num, den);
+
+            num /= g;
+            den /= g;
+        }
+
+        if !self.mixed || den == 0 {
+            return (neg, None, Some((num, den)));
+        }
+
+        match (num / den, num % den) {
+            (whole, 0)   => (neg, Some(whole), None),
+            (0, rem)     => (neg, None, Some((rem, den))),
+            (whole, rem) => (neg, Some(whole), Some((rem, den))),
+        }
+    }
+}
+
+impl Format<(i64, u64)> for Fraction {
+    fn len(&self, x: &(i64, u64)) -> usize {
+        let (neg, whole, frac) = self.parts(x);
+
+        let mut len = if neg { 1 } else { 0 };
+
+        if let Some(whole) = whole {
+            len += Show::len(&whole);
+        }
+
+        if let Some((num, den)) = frac {
+            if whole.is_some() { len += 1; }
+
+            len += Show::len(&num) + 1 + Show::len(&den);
+        }
+
+        len
+    }
+
+    fn write<W: io::Write>(&self, x: &(i64, u64), w: &mut W) -> io::Result<usize> {
+        let (neg, whole, frac) = self.parts(x);
+
+        let mut written = 0;
+
+        if neg {
+            written += Show::write(&'-', w)?;
+        }
+
+        if let Some(whole) = whole {
+            written += Show::write(&whole, w)?;
+        }
+
+        if let Some((num, den)) = frac {
+            if whole.is_some() {
+                written += Show::write(&' ', w)?;
+            }
+
+            written += Show::write(&num, w)?;
+            written += Show::write(&'/', w)?;
+            written += Show::write(&den, w)?;
+        }
+
+        Ok(written)
+    }
+}
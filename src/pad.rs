@@ -0,0 +1,88 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::io;
+
+use spec::{Align, Overflow, Width};
+use traits::{Show, Format};
+
+/// Pad a value with `fill` until it's at least `width` characters wide. A value that's already
+/// wider is handled according to `overflow`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Pad, Align, Overflow};
+/// # fn main() {
+/// let pad = Pad { width: 6, fill: '.', align: Align::Right, overflow: Overflow::Expand };
+/// let s   = scat!('[', pad; 42, ']');
+///
+/// assert_eq!(s, "[....42]");
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Pad {
+    pub width:    usize,
+    pub fill:     char,
+    pub align:    Align,
+    pub overflow: Overflow,
+}
+
+// Counts characters rather than bytes, so that multi-byte values are padded correctly.
+struct CharCounter(usize);
+
+impl io::Write for CharCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.iter().filter(|&&b| b & 0xc0 != 0x80).count();
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+// How many characters wide `t` is.
+fn chars<T: Show>(t: &T) -> usize {
+    let mut counter = CharCounter(0);
+
+    Show::write(t, &mut counter).ok();
+
+    counter.0
+}
+
+impl From<Width> for Pad {
+    fn from(w: Width) -> Pad {
+        Pad { width: w.min, fill: w.fill, align: w.align, overflow: w.overflow }
+    }
+}
+
+impl From<Pad> for Width {
+    fn from(p: Pad) -> Width {
+        Width { min: p.width, fill: p.fill, align: p.align, overflow: p.overflow }
+    }
+}
+
+impl<T> Format<T> for Pad
+where T: Show {
+    fn len(&self, t: &T) -> usize {
+        Width::from(*self).len(t, chars(t))
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        Width::from(*self).write(t, chars(t), w)
+    }
+}
@@ -0,0 +1,322 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+use std::convert::TryFrom;
+use std::io;
+
+use traits::{Show, Format};
+
+/// The plural categories of the Unicode CLDR. Each language uses some of them, and always
+/// `Other`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// The category's name in CLDR, like "few".
+    pub fn name(self) -> &'static str {
+        match self {
+            PluralCategory::Zero  => "zero",
+            PluralCategory::One   => "one",
+            PluralCategory::Two   => "two",
+            PluralCategory::Few   => "few",
+            PluralCategory::Many  => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// A language's CLDR rules for which plural category a whole number falls in.
+///
+/// # Examples
+/// ```
+/// # use cats::{PluralRules, PluralCategory::*};
+/// let ru = PluralRules::for_locale("ru-RU");
+///
+/// assert_eq!([1, 2, 5, 11, 21, 22, 25].map(|n| ru.category(n)),
+///            [One, Few, Many, Many, One, Few, Many]);
+///
+/// let ar = PluralRules::for_locale("ar");
+///
+/// assert_eq!([0, 1, 2, 3, 11, 100].map(|n| ar.category(n)), [Zero, One, Two, Few, Many, Other]);
+/// assert_eq!(PluralRules::for_locale("ja").category(1), Other);
+/// ```
+#[derive(Clone, Copy)]
+pub struct PluralRules(fn(u64) -> PluralCategory);
+
+use self::PluralCategory::*;
+
+fn other(_: u64) -> PluralCategory { Other }
+
+fn one(n: u64) -> PluralCategory {
+    if n == 1 { One } else { Other }
+}
+
+fn zero_one(n: u64) -> PluralCategory {
+    if n <= 1 { One } else { Other }
+}
+
+// French, and Portuguese outside Portugal. Exact millions are "many", as in "1 million de".
+fn zero_one_millions(n: u64) -> PluralCategory {
+    match n {
+        0 | 1                   => One,
+        _ if n % 1_000_000 == 0 => Many,
+        _                       => Other,
+    }
+}
+
+fn one_millions(n: u64) -> PluralCategory {
+    match n {
+        1                                 => One,
+        _ if n != 0 && n % 1_000_000 == 0 => Many,
+        _                                 => Other,
+    }
+}
+
+fn east_slavic(n: u64) -> PluralCategory {
+    match (n % 10, n % 100) {
+        (1, r) if r != 11                     => One,
+        (2..=4, r) if !(12..=14).contains(&r) => Few,
+        _                                     => Many,
+    }
+}
+
+fn polish(n: u64) -> PluralCategory {
+    match (n, n % 10, n % 100) {
+        (1, _, _)                                => One,
+        (_, 2..=4, r) if !(12..=14).contains(&r) => Few,
+        _                                        => Many,
+    }
+}
+
+fn czech(n: u64) -> PluralCategory {
+    match n {
+        1     => One,
+        2..=4 => Few,
+        _     => Other,
+    }
+}
+
+fn south_slavic(n: u64) -> PluralCategory {
+    match (n % 10, n % 100) {
+        (1, r) if r != 11                     => One,
+        (2..=4, r) if !(12..=14).contains(&r) => Few,
+        _                                     => Other,
+    }
+}
+
+fn slovenian(n: u64) -> PluralCategory {
+    match n % 100 {
+        1     => One,
+        2     => Two,
+        3 | 4 => Few,
+        _     => Other,
+    }
+}
+
+fn lithuanian(n: u64) -> PluralCategory {
+    match (n % 10, n % 100) {
+        (_, 11..=19) => Other,
+        (1, _)       => One,
+        (2..=9, _)   => Few,
+        _            => Other,
+    }
+}
+
+fn latvian(n: u64) -> PluralCategory {
+    match (n % 10, n % 100) {
+        (0, _) | (_, 11..=19) => Zero,
+        (1, _)                => One,
+        _                     => Other,
+    }
+}
+
+fn romanian(n: u64) -> PluralCategory {
+    match (n, n % 100) {
+        (1, _)               => One,
+        (0, _) | (_, 1..=19) => Few,
+        _                    => Other,
+    }
+}
+
+fn arabic(n: u64) -> PluralCategory {
+    match (n, n % 100) {
+        (0, _)       => Zero,
+        (1, _)       => One,
+        (2, _)       => Two,
+        (_, 3..=10)  => Few,
+        (_, 11..=99) => Many,
+        _            => Other,
+    }
+}
+
+fn hebrew(n: u64) -> PluralCategory {
+    match n {
+        1 => One,
+        2 => Two,
+        _ => Other,
+    }
+}
+
+fn irish(n: u64) -> PluralCategory {
+    match n {
+        1      => One,
+        2      => Two,
+        3..=6  => Few,
+        7..=10 => Many,
+        _      => Other,
+    }
+}
+
+fn welsh(n: u64) -> PluralCategory {
+    match n {
+        0 => Zero,
+        1 => One,
+        2 => Two,
+        3 => Few,
+        6 => Many,
+        _ => Other,
+    }
+}
+
+impl PluralRules {
+    /// The rules for a locale such as "en", "pt-PT" or "sr_Latn". A language this doesn't know
+    /// puts every number in `Other`, as the CLDR does.
+    pub fn for_locale(locale: &str) -> PluralRules {
+        // A split on `['-', '_']` can't be iterated from the back on Rust 1.70.
+        #[allow(clippy::manual_pattern_char_comparison)]
+        let mut parts = locale.split(|c| c == '-' || c == '_');
+        let lang      = parts.next().unwrap_or("").to_ascii_lowercase();
+        let region    = parts.next_back().unwrap_or("").to_ascii_lowercase();
+
+        PluralRules(match &lang[..] {
+            // Portugal's Portuguese counts like Spanish, not like Brazil's.
+            "pt" if region == "pt" => one_millions,
+            "fr" | "pt"            => zero_one_millions,
+            "es" | "it" | "ca"     => one_millions,
+
+            "hi" | "bn" | "fa" | "gu" | "kn" | "am" | "zu" => zero_one,
+
+            "en" | "de" | "nl" | "sv" | "da" | "nb" | "nn" | "no" | "fi" | "et" | "el" | "hu" |
+            "tr" | "bg" | "eu" | "gl" | "af" | "sq" | "az" | "ka" | "ur" | "sw" | "ta" | "te" |
+            "ml" | "mr" | "kk" | "mn" | "ne" | "uz" | "fy" | "lb" | "rm" => one,
+
+            "ru" | "uk" | "be" => east_slavic,
+            "pl"               => polish,
+            "cs" | "sk"        => czech,
+            "hr" | "sr" | "bs" => south_slavic,
+            "sl"               => slovenian,
+            "lt"               => lithuanian,
+            "lv"               => latvian,
+            "ro" | "mo"        => romanian,
+            "ar"               => arabic,
+            "he" | "iw"        => hebrew,
+            "ga"               => irish,
+            "cy"               => welsh,
+
+            _ => other,
+        })
+    }
+
+    /// The category that `n` falls in.
+    pub fn category(&self, n: u64) -> PluralCategory {
+        (self.0)(n)
+    }
+}
+
+/// Like `Plural`, but for any language: show a count with the form of a noun that its
+/// `PluralRules` pick. Categories missing from `forms` use `other`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{PluralForms, PluralRules, PluralCategory::*};
+/// # fn main() {
+/// let files = PluralForms {
+///     rules:      PluralRules::for_locale("ru"),
+///     forms:      &[(One, "файл"), (Few, "файла")],
+///     other:      "файлов",
+///     show_count: true,
+/// };
+///
+/// assert_eq!(scat!(files; 1, ", ", files; 3, ", ", files; 11, ", ", files; 21),
+///            "1 файл, 3 файла, 11 файлов, 21 файл");
+/// # }
+/// ```
+pub struct PluralForms<'x> {
+    pub rules:      PluralRules,
+    pub forms:      &'x [(PluralCategory, &'x str)],
+    pub other:      &'x str,
+    pub show_count: bool,
+}
+
+impl<'x> PluralForms<'x> {
+    fn noun(&self, n: u128) -> &'x str {
+        let category = self.rules.category(plural_operand(n));
+
+        self.forms.iter().find(|f| f.0 == category).map_or(self.other, |f| f.1)
+    }
+
+    fn len_of<T: Show>(&self, n: &T, noun: &str) -> usize {
+        if self.show_count { Show::len(n) + 1 + noun.len() } else { noun.len() }
+    }
+
+    fn write_of<T: Show, W: io::Write>(&self, n: &T, noun: &str, w: &mut W) -> io::Result<usize> {
+        if self.show_count {
+            Ok(Show::write(n, w)? + Show::write(&' ', w)? + Show::write(noun, w)?)
+        } else {
+            Show::write(noun, w)
+        }
+    }
+}
+
+// A count for `PluralRules`, which only look at its magnitude. One too big for a `u64` keeps its
+// last eighteen digits, which is all any rule looks at, and is kept from looking small.
+fn plural_operand(n: u128) -> u64 {
+    match u64::try_from(n) {
+        Ok(n)  => n,
+        Err(_) => (n % 1_000_000_000_000_000_000 + 1_000_000_000_000_000_000) as u64,
+    }
+}
+
+macro_rules! impl_plural_forms {
+    (@unsigned $($t:ty),*) => ($(
+        impl<'x> Format<$t> for PluralForms<'x> {
+            fn len(&self, n: &$t) -> usize { self.len_of(n, self.noun(*n as u128)) }
+            fn write<W: io::Write>(&self, n: &$t, w: &mut W) -> io::Result<usize> {
+                self.write_of(n, self.noun(*n as u128), w)
+            }
+        }
+    )*);
+
+    (@signed $($t:ty),*) => ($(
+        impl<'x> Format<$t> for PluralForms<'x> {
+            fn len(&self, n: &$t) -> usize { self.len_of(n, self.noun(n.unsigned_abs() as u128)) }
+            fn write<W: io::Write>(&self, n: &$t, w: &mut W) -> io::Result<usize> {
+                self.write_of(n, self.noun(n.unsigned_abs() as u128), w)
+            }
+        }
+    )*);
+}
+
+impl_plural_forms!(@unsigned u8, u16, u32, u64, u128, usize);
+impl_plural_forms!(@signed i8, i16, i32, i64, i128, isize);
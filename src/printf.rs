@@ -0,0 +1,678 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! A runtime `printf`, for format strings that aren't known until the program runs, like ones read
+//! from a config file, or for code being ported from C.
+//!
+//! A conversion is written `%[flags][width][.precision][length]type`, as in C:
+//!
+//! - `flags` are any of '-' (left-align), '+' (always show a sign), ' ' (a space where a '+' would
+//!   go), '#' (the alternate form: "0x" for hex, a leading '0' for octal, and always a decimal
+//!   point for floats) and '0' (pad numbers with zeros).
+//! - `width` and `precision` are numbers, or '*' to take them from the next argument.
+//! - `length` is one of "hh", "h", "l", "ll", "j", "z", "t", "L" or "q". It only matters when a
+//!   negative integer is shown as unsigned, where it sets how many bits it's wrapped to. Without
+//!   one that's 32, as for C's `int`.
+//! - `type` is 'd' or 'i' for a decimal integer, 'u', 'o', 'x' or 'X' for unsigned ones, 'c' for
+//!   a character, 's' for a string, 'f', 'e' or 'g' for floats (uppercase for uppercase output),
+//!   or '%' for a literal '%'.
+//!
+//! Integers are shown with `FormattedInt` and strings with `FormattedStr`, padded with `Pad`. This
+//! differs from C in three ways:
+//!
+//! - A string's width and precision count characters, not bytes.
+//! - An integer isn't cut down to the type its length names, so `%hhd` shows 300 as "300" and
+//!   `%hx` shows 0x1ffff as "1ffff", where C would show "44" and "ffff". Only a negative integer
+//!   shown as unsigned is wrapped, as described above.
+//! - A precision can be at most 65535, and a larger one is an `Error`.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::printf::{self, Printf};
+//!
+//! # fn main() {
+//! let s = printf::format("count=%5d name=%-10s|", &[42.into(), "Tom".into()]).unwrap();
+//!
+//! assert_eq!(s, "count=   42 name=Tom       |");
+//!
+//! // Parse once and reuse, and show the result anywhere a `Show` goes.
+//! let row = Printf::parse("%-8s %6.2f%%").unwrap();
+//!
+//! assert_eq!(scat!('|', row.bind(&["cpu".into(), 93.456.into()]).unwrap(), '|'),
+//!            "|cpu       93.46%|");
+//! # }
+//! ```
+
+use std::cmp;
+use std::convert::TryFrom;
+use std::error;
+use std::fmt;
+use std::io;
+
+use pad::Pad;
+use spec::{Align, Precision, Width};
+use text::FormattedStr;
+use traits::{Show, Format, Formatted, FormattedInt, SignPolicy, Utf8Write, measure};
+
+const OCTAL_DIGITS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7'];
+
+// Floats are shown with `std::fmt`, which panics on a precision any larger than this.
+const MAX_PRECISION: usize = u16::MAX as usize;
+
+/// An argument to `printf`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Arg<'a> {
+    Int(i64),
+    Uint(u64),
+    Float(f64),
+    Char(char),
+    Str(&'a str),
+}
+
+macro_rules! impl_from {
+    ($($variant:ident($wide:ty): $($t:ty),*;)*) => ($($(
+        impl<'a> From<$t> for Arg<'a> {
+            fn from(x: $t) -> Arg<'a> { Arg::$variant(x as $wide) }
+        }
+    )*)*)
+}
+
+impl_from! {
+    Int(i64):   i8, i16, i32, i64, isize;
+    Uint(u64):  u8, u16, u32, u64, usize;
+    Float(f64): f32, f64;
+}
+
+impl<'a> From<char> for Arg<'a> {
+    fn from(c: char) -> Arg<'a> { Arg::Char(c) }
+}
+
+impl<'a> From<&'a str> for Arg<'a> {
+    fn from(s: &'a str) -> Arg<'a> { Arg::Str(s) }
+}
+
+impl<'a> From<&'a String> for Arg<'a> {
+    fn from(s: &'a String) -> Arg<'a> { Arg::Str(s) }
+}
+
+/// What went wrong with a `printf` format string or its arguments.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The conversion starting at this byte of the format string isn't valid.
+    BadSpec(usize),
+
+    /// The format string needs the argument at this index, but there are fewer arguments.
+    MissingArg(usize),
+
+    /// The argument at `index` can't be shown by the `conversion` it was given to. A '*' width or
+    /// precision takes an integer.
+    WrongType { index: usize, conversion: char },
+
+    /// The argument at this index, taken by a '*' precision, is over 65535.
+    BadPrecision(usize),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::BadSpec(offset) => {
+                write!(f, "invalid conversion at byte {} of format string", offset)
+            },
+            Error::MissingArg(index) => {
+                write!(f, "format string needs argument {}, which is missing", index)
+            },
+            Error::WrongType { index, conversion } => {
+                write!(f, "argument {} can't be shown with '{}'", index, conversion)
+            },
+            Error::BadPrecision(index) => {
+                write!(f, "argument {} is too large for a precision", index)
+            },
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+/// Format `args` with a C-style format string.
+///
+/// # Examples
+/// ```
+/// # use cats::printf::{format, Error};
+/// assert_eq!(format("%+.3d|%-4c|%.2s", &[7.into(), 'x'.into(), "Mittens".into()]).unwrap(),
+///            "+007|x   |Mi");
+/// assert_eq!(format("%#x %#o %X %hhx", &[255.into(), 8.into(), 48879.into(), (-1).into()])
+///                .unwrap(),
+///            "0xff 010 BEEF ff");
+/// assert_eq!(format("%08.3f %e %g %G", &[(-3.14159).into(), 1234.5.into(), 0.0001.into(),
+///                                        1e-10.into()]).unwrap(),
+///            "-003.142 1.234500e+03 0.0001 1E-10");
+/// assert_eq!(format("[%*d] %c", &[(-5).into(), 42.into(), 65.into()]).unwrap(), "[42   ] A");
+///
+/// assert_eq!(format("%d", &["Tom".into()]), Err(Error::WrongType { index: 0, conversion: 'd' }));
+/// assert_eq!(format("%d %d", &[1.into()]), Err(Error::MissingArg(1)));
+/// assert_eq!(format("100%", &[]), Err(Error::BadSpec(3)));
+/// assert_eq!(format("%.*f", &[70000.into(), 1.0.into()]), Err(Error::BadPrecision(0)));
+/// ```
+pub fn format(fmt: &str, args: &[Arg]) -> Result<String, Error> {
+    let printf = Printf::parse(fmt)?;
+    let bound  = printf.bind(args)?;
+
+    let mut buf = Vec::with_capacity(bound.len());
+
+    // A printf field always grows to fit its value, so writing to a Vec can't fail, and everything
+    // written comes from strs.
+    bound.write(&mut buf).ok();
+
+    Ok(String::from_utf8(buf).expect("printf generated invalid utf-8"))
+}
+
+/// A parsed format string, ready to be given arguments with `bind`.
+pub struct Printf<'f> {
+    pieces: Vec<Piece<'f>>,
+}
+
+enum Piece<'f> {
+    Lit(&'f str),
+    Conv(Conv),
+}
+
+#[derive(Clone, Copy)]
+enum Count {
+    Is(usize),
+    Arg,
+}
+
+// How many bits a negative integer is wrapped to when it's shown as unsigned.
+#[derive(Clone, Copy)]
+enum Length {
+    Char,
+    Short,
+    Int,
+    Long,
+}
+
+impl Length {
+    fn wrap(self, x: i64) -> u64 {
+        match self {
+            Length::Char  => x as u8 as u64,
+            Length::Short => x as u16 as u64,
+            Length::Int   => x as u32 as u64,
+            Length::Long  => x as u64,
+        }
+    }
+}
+
+struct Conv {
+    left:  bool,
+    plus:  bool,
+    space: bool,
+    alt:   bool,
+    zero:  bool,
+
+    width:     Count,
+    precision: Option<Count>,
+    length:    Length,
+    ty:        char,
+}
+
+// Parse a run of decimal digits at the start of `s`, if there is one, returning it and how many
+// bytes it took.
+fn parse_num(s: &[u8]) -> Option<(Option<usize>, usize)> {
+    let len = s.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    if len == 0 {
+        return Some((None, 0));
+    }
+
+    let mut n: usize = 0;
+
+    for &b in &s[..len] {
+        n = n.checked_mul(10)?.checked_add((b - b'0') as usize)?;
+    }
+
+    Some((Some(n), len))
+}
+
+// Parse a width or precision at the start of `s`.
+fn parse_count(s: &[u8]) -> Option<(Option<Count>, usize)> {
+    if s.first() == Some(&b'*') {
+        return Some((Some(Count::Arg), 1));
+    }
+
+    parse_num(s).map(|(n, len)| (n.map(Count::Is), len))
+}
+
+// Parse the conversion at the start of `s`, just after its '%', returning it and how many bytes it
+// took.
+fn parse_conv(s: &[u8]) -> Option<(Conv, usize)> {
+    let mut conv = Conv {
+        left:  false,
+        plus:  false,
+        space: false,
+        alt:   false,
+        zero:  false,
+
+        width:     Count::Is(0),
+        precision: None,
+        length:    Length::Int,
+        ty:        'd',
+    };
+
+    let mut i = 0;
+
+    while let Some(&b) = s.get(i) {
+        match b {
+            b'-' => conv.left  = true,
+            b'+' => conv.plus  = true,
+            b' ' => conv.space = true,
+            b'#' => conv.alt   = true,
+            b'0' => conv.zero  = true,
+            _    => break,
+        }
+
+        i += 1;
+    }
+
+    let (width, len) = parse_count(&s[i..])?;
+    conv.width = width.unwrap_or(Count::Is(0));
+    i += len;
+
+    if s.get(i) == Some(&b'.') {
+        let (precision, len) = parse_count(&s[i + 1..])?;
+
+        if let Some(Count::Is(p)) = precision {
+            if p > MAX_PRECISION {
+                return None;
+            }
+        }
+
+        // A '.' on its own means a precision of zero.
+        conv.precision = Some(precision.unwrap_or(Count::Is(0)));
+        i += 1 + len;
+    }
+
+    let (length, len) = match (s.get(i), s.get(i + 1)) {
+        (Some(b'h'), Some(b'h')) => (Length::Char, 2),
+        (Some(b'l'), Some(b'l')) => (Length::Long, 2),
+        (Some(b'h'), _)          => (Length::Short, 1),
+        (Some(b'l'), _) | (Some(b'j'), _) | (Some(b'z'), _) | (Some(b't'), _) |
+        (Some(b'L'), _) | (Some(b'q'), _) => (Length::Long, 1),
+        _ => (Length::Int, 0),
+    };
+
+    conv.length = length;
+    i += len;
+
+    match s.get(i) {
+        Some(&b) if b"diouxXcsfFeEgG".contains(&b) => conv.ty = b as char,
+        _                                          => return None,
+    }
+
+    Some((conv, i + 1))
+}
+
+impl<'f> Printf<'f> {
+    /// Parse a format string, checking that every conversion in it is valid.
+    pub fn parse(fmt: &'f str) -> Result<Printf<'f>, Error> {
+        let mut pieces = Vec::new();
+        let mut rest   = 0;
+
+        while let Some(i) = fmt[rest..].find('%').map(|i| rest + i) {
+            if i > rest {
+                pieces.push(Piece::Lit(&fmt[rest..i]));
+            }
+
+            if fmt[i + 1..].starts_with('%') {
+                pieces.push(Piece::Lit("%"));
+                rest = i + 2;
+                continue;
+            }
+
+            let (conv, len) = parse_conv(&fmt.as_bytes()[i + 1..]).ok_or(Error::BadSpec(i))?;
+
+            pieces.push(Piece::Conv(conv));
+            rest = i + 1 + len;
+        }
+
+        if rest < fmt.len() {
+            pieces.push(Piece::Lit(&fmt[rest..]));
+        }
+
+        Ok(Printf { pieces })
+    }
+
+    /// Pair this format with its arguments, checking that there are enough of them and that each
+    /// is the right type for its conversion. Any arguments left over are ignored, as in C.
+    pub fn bind<'p, 'a>(&'p self, args: &'p [Arg<'a>]) -> Result<Bound<'p, 'a>, Error> {
+        let mut next  = 0;
+        let mut bound = Vec::with_capacity(self.pieces.len());
+
+        let mut take = || -> Result<(usize, &'p Arg<'a>), Error> {
+            let arg = args.get(next).ok_or(Error::MissingArg(next))?;
+            next += 1;
+
+            Ok((next - 1, arg))
+        };
+
+        for piece in &self.pieces {
+            let conv = match *piece {
+                Piece::Lit(s)      => { bound.push(Segment::Lit(s)); continue; },
+                Piece::Conv(ref c) => c,
+            };
+
+            // A '*' takes an integer argument, and a negative one is a '-' flag for a width and
+            // no precision at all for a precision. Only a precision has a `limit` short of
+            // `i64::MAX`, since a float can't be shown with more digits than `MAX_PRECISION`.
+            let mut count = |count: Count, limit: i64| -> Result<Option<i64>, Error> {
+                let (index, n) = match count {
+                    Count::Is(n) => return Ok(Some(cmp::min(n, i64::MAX as usize) as i64)),
+                    Count::Arg   => match take()? {
+                        (index, &Arg::Int(n))  => (index, n),
+                        (index, &Arg::Uint(n)) => (index, cmp::min(n, i64::MAX as u64) as i64),
+                        (index, _) => return Err(Error::WrongType { index, conversion: '*' }),
+                    },
+                };
+
+                if n > limit {
+                    return Err(Error::BadPrecision(index));
+                }
+
+                Ok(Some(n))
+            };
+
+            let width     = count(conv.width, i64::MAX)?.unwrap_or(0);
+            let precision = match conv.precision {
+                Some(p) => {
+                    count(p, MAX_PRECISION as i64)?.filter(|&p| p >= 0).map(|p| p as usize)
+                },
+                None => None,
+            };
+
+            let (index, arg) = take()?;
+
+            let fits = match (conv.ty, *arg) {
+                ('d', Arg::Int(_)) | ('d', Arg::Uint(_)) |
+                ('i', Arg::Int(_)) | ('i', Arg::Uint(_)) => true,
+
+                ('u', Arg::Int(_)) | ('u', Arg::Uint(_)) |
+                ('o', Arg::Int(_)) | ('o', Arg::Uint(_)) |
+                ('x', Arg::Int(_)) | ('x', Arg::Uint(_)) |
+                ('X', Arg::Int(_)) | ('X', Arg::Uint(_)) => true,
+
+                ('c', _)                                 => as_char(arg).is_some(),
+                ('s', Arg::Str(_))                       => true,
+
+                (ty, Arg::Float(_))                      => "fFeEgG".contains(ty),
+                _                                        => false,
+            };
+
+            if !fits {
+                return Err(Error::WrongType { index, conversion: conv.ty });
+            }
+
+            bound.push(Segment::Conv {
+                conv,
+                arg,
+                width: width.unsigned_abs() as usize,
+                left:  conv.left || width < 0,
+                precision,
+            });
+        }
+
+        Ok(Bound(bound))
+    }
+}
+
+// The character a '%c' argument stands for, which may be given as its code point.
+fn as_char(arg: &Arg) -> Option<char> {
+    match *arg {
+        Arg::Char(c) => Some(c),
+        Arg::Int(n)  => u32::try_from(n).ok().and_then(char::from_u32),
+        Arg::Uint(n) => u32::try_from(n).ok().and_then(char::from_u32),
+        _            => None,
+    }
+}
+
+/// A `Printf` with its arguments, which shows the formatted result.
+pub struct Bound<'p, 'a: 'p>(Vec<Segment<'p, 'a>>);
+
+enum Segment<'p, 'a: 'p> {
+    Lit(&'p str),
+    Conv {
+        conv:      &'p Conv,
+        arg:       &'p Arg<'a>,
+        width:     usize,
+        left:      bool,
+        precision: Option<usize>,
+    },
+}
+
+impl<'p, 'a> Show for Bound<'p, 'a> {
+    fn len(&self) -> usize {
+        measure(|w| self.write(w))
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        let mut written = 0;
+
+        for piece in &self.0 {
+            written += match *piece {
+                Segment::Lit(s) => Show::write(s, w)?,
+                Segment::Conv { conv, arg, width, left, precision } => {
+                    let field = Field { conv, width, left, precision };
+
+                    field.write(arg, w)?
+                },
+            };
+        }
+
+        Ok(written)
+    }
+}
+
+// A conversion along with its width and precision, once any '*'s have been filled in.
+struct Field<'p> {
+    conv:      &'p Conv,
+    width:     usize,
+    left:      bool,
+    precision: Option<usize>,
+}
+
+impl<'p> Field<'p> {
+    fn pad(&self) -> Pad {
+        let align = if self.left { Align::Left } else { Align::Right };
+
+        Pad::from(Width::new(self.width).align(align))
+    }
+
+    fn write<W: io::Write>(&self, arg: &Arg, w: &mut W) -> io::Result<usize> {
+        if let ('c', Some(c)) = (self.conv.ty, as_char(arg)) {
+            return Format::write(&self.pad(), &c, w);
+        }
+
+        match (self.conv.ty, *arg) {
+            ('d', Arg::Int(n)) | ('i', Arg::Int(n)) => self.write_int(n, n == 0, w),
+            (_, Arg::Int(n)) if n < 0               => {
+                self.write_int(self.conv.length.wrap(n), false, w)
+            },
+            (_, Arg::Int(n))                        => self.write_int(n as u64, n == 0, w),
+            (_, Arg::Uint(n))                       => self.write_int(n, n == 0, w),
+            (_, Arg::Char(c))                       => Format::write(&self.pad(), &c, w),
+            (_, Arg::Str(s))                        => {
+                let fmt = FormattedStr {
+                    width:     self.pad().into(),
+                    precision: self.precision.map(Precision),
+                };
+
+                Format::write(&fmt, &s, w)
+            },
+            (_, Arg::Float(x))                      => self.write_float(x, w),
+        }
+    }
+
+    fn write_int<T, W>(&self, n: T, is_zero: bool, w: &mut W) -> io::Result<usize>
+    where for<'x> FormattedInt<'x>: Format<T>, W: io::Write {
+        let conv   = self.conv;
+        let signed = conv.ty == 'd' || conv.ty == 'i';
+
+        let sign = match (signed, conv.plus, conv.space) {
+            (true, true, _)     => "+",
+            (true, false, true) => " ",
+            _                   => "",
+        };
+
+        let (f, prefix) = match conv.ty {
+            'x' => (FormattedInt::hex(), "0x"),
+            'X' => (FormattedInt::hex().upper(), "0X"),
+            'o' => (FormattedInt::with_digits(OCTAL_DIGITS), "0"),
+            _   => (FormattedInt::decimal(), ""),
+        };
+
+        let mut f = f.sign(match sign {
+            "+" => SignPolicy::Plus,
+            " " => SignPolicy::Space,
+            _   => SignPolicy::Empty,
+        });
+
+        // The alternate form's prefix goes on anything but zero, except that octal only needs its
+        // '0' if the precision doesn't already give it one.
+        if conv.alt && !is_zero {
+            let digits = Format::len(&f, &n);
+
+            if conv.ty != 'o' || self.precision.map_or(true, |p| p <= digits) {
+                f = f.prefix(prefix);
+            }
+        }
+
+        // As in C, a precision of zero shows nothing at all for zero, bar a sign or octal's '0'.
+        if is_zero && self.precision == Some(0) {
+            let s = if conv.ty == 'o' && conv.alt { "0" } else { sign };
+
+            return Format::write(&self.pad(), &s, w);
+        }
+
+        match self.precision {
+            Some(p)                         => f = f.min_len(p),
+            None if conv.zero && !self.left => f = f.min_len(self.width).full_width(),
+            None                            => (),
+        }
+
+        Format::write(&self.pad(), &Formatted(f, &n), w)
+    }
+
+    fn write_float<W: io::Write>(&self, x: f64, w: &mut W) -> io::Result<usize> {
+        let conv = self.conv;
+
+        let sign = match () {
+            _ if x.is_sign_negative() => "-",
+            _ if conv.plus            => "+",
+            _ if conv.space           => " ",
+            _                         => "",
+        };
+
+        let x         = x.abs();
+        let precision = self.precision.unwrap_or(6);
+
+        let mut body = match conv.ty {
+            _ if x.is_nan()      => String::from("nan"),
+            _ if x.is_infinite() => String::from("inf"),
+            'f' | 'F'            => fixed(x, precision, conv.alt),
+            'e' | 'E'            => exponent(x, precision, conv.alt),
+            _                    => general(x, precision, conv.alt),
+        };
+
+        if conv.ty.is_ascii_uppercase() {
+            body.make_ascii_uppercase();
+        }
+
+        // Everything here is ASCII, so bytes are characters.
+        let fill = self.width.saturating_sub(sign.len() + body.len());
+
+        let mut w = Utf8Write(w);
+
+        Ok(if self.left {
+            w.push_str(sign)? + w.push_str(&body)? + w.push_n(' ', fill)?
+        } else if conv.zero && x.is_finite() {
+            w.push_str(sign)? + w.push_n('0', fill)? + w.push_str(&body)?
+        } else {
+            w.push_n(' ', fill)? + w.push_str(sign)? + w.push_str(&body)?
+        })
+    }
+}
+
+// `%f`: `precision` digits after the point.
+fn fixed(x: f64, precision: usize, alt: bool) -> String {
+    let mut s = format!("{:.*}", precision, x);
+
+    if alt && precision == 0 {
+        s.push('.');
+    }
+
+    s
+}
+
+// `%e`: one digit before the point and `precision` after, then an exponent of at least two digits.
+fn exponent(x: f64, precision: usize, alt: bool) -> String {
+    let s      = format!("{:.*e}", precision, x);
+    let (m, e) = s.split_at(s.find('e').unwrap_or(s.len()));
+    let e: i32 = e[1..].parse().unwrap_or(0);
+
+    let point = if alt && precision == 0 { "." } else { "" };
+    let sign  = if e < 0 { '-' } else { '+' };
+
+    format!("{}{}e{}{:02}", m, point, sign, e.abs())
+}
+
+// `%g`: `precision` significant digits, in whichever of `%f` or `%e` suits the exponent, with
+// trailing zeros removed unless `alt` is set.
+fn general(x: f64, precision: usize, alt: bool) -> String {
+    let precision = cmp::max(precision, 1);
+
+    // The exponent after rounding, which may be one more than before.
+    let exp = if x == 0.0 {
+        0
+    } else {
+        let s = format!("{:.*e}", precision - 1, x);
+
+        s[s.find('e').map_or(s.len(), |i| i + 1)..].parse().unwrap_or(0)
+    };
+
+    let mut s = if exp >= -4 && exp < precision as i64 {
+        fixed(x, (precision as i64 - 1 - exp) as usize, alt)
+    } else {
+        exponent(x, precision - 1, alt)
+    };
+
+    if !alt {
+        let end      = s.find('e').unwrap_or(s.len());
+        let mantissa = &s[..end];
+
+        if mantissa.contains('.') {
+            let trimmed = mantissa.trim_end_matches('0').trim_end_matches('.').len();
+
+            s.replace_range(trimmed..end, "");
+        }
+    }
+
+    s
+}
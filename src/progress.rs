@@ -0,0 +1,326 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! Progress bars and spinners for command-line tools.
+//!
+//! A `ProgressBar` is a `Show` that renders as one line of a fixed width, so each update can be
+//! drawn over the last by catting it after a carriage return. Progress usually goes to stderr,
+//! which isn't buffered, so it shows up straight away and stays out of the way of piped output:
+//!
+//! ```no_run
+//! # #[macro_use] extern crate cats;
+//! # use cats::progress::ProgressBar;
+//! # fn main() {
+//! # let files: Vec<&str> = Vec::new();
+//! let mut bar = ProgressBar::new(files.len() as u64);
+//!
+//! for file in &files {
+//!     // ... process `file` ...
+//!     bar.inc(1);
+//!     ecat!('\r', bar);
+//! }
+//!
+//! ecatln!();
+//! # }
+//! ```
+//!
+//! The bar tracks the time since it was made to work out the rate and the time left. `at` sets
+//! both the count and the time instead, which is how the examples here stay predictable.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use std::time::Duration;
+//! use cats::progress::ProgressBar;
+//!
+//! # fn main() {
+//! let mut bar = ProgressBar::new(2000).width(50);
+//!
+//! bar.at(500, Duration::from_secs(4));
+//!
+//! assert_eq!(scat!(bar), "[██████░░░░░░░░░░░░░░░░░░]  25%  125.0/s ETA 00:12");
+//!
+//! bar.at(2000, Duration::from_secs(16));
+//!
+//! assert_eq!(scat!(bar), "[████████████████████████] 100%  125.0/s ETA 00:00");
+//! # }
+//! ```
+
+use std::io;
+use std::time::{Duration, Instant};
+
+use num::FixedPoint;
+use pad::Pad;
+use spec::{Align, Overflow};
+use term;
+use traits::{Show, Format, FormattedInt, Rep, measure, write_counted};
+
+const TWO_DIGITS: FormattedInt<'static> = FormattedInt::decimal().min_len(2);
+
+// Right-align in a field `width` characters wide.
+const fn right(width: usize) -> Pad {
+    Pad { width, fill: ' ', align: Align::Right, overflow: Overflow::Expand }
+}
+
+// Items per second, with a k, M, G or T once it's in the thousands.
+struct Rate(Option<f64>);
+
+impl Rate {
+    // The rate in tenths, and its unit.
+    fn parts(&self) -> Option<(u64, &'static str)> {
+        let mut rate = self.0?;
+        let mut unit = 0;
+
+        while rate >= 999.95 && unit < 4 {
+            rate /= 1000.0;
+            unit += 1;
+        }
+
+        Some(((rate * 10.0).round() as u64, ["", "k", "M", "G", "T"][unit]))
+    }
+}
+
+impl Show for Rate {
+    fn len(&self) -> usize {
+        match self.parts() {
+            Some((tenths, unit)) => Format::len(&FixedPoint { scale: 1 }, &tenths) + unit.len() + 2,
+            None                 => 3,
+        }
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        match self.parts() {
+            Some((tenths, unit)) => Ok(Format::write(&FixedPoint { scale: 1 }, &tenths, w)? +
+                                       Show::write(unit, w)? + Show::write("/s", w)?),
+            None                 => Show::write("-/s", w),
+        }
+    }
+}
+
+// The time left, as minutes and seconds, with hours in front when there are any.
+struct Eta(Option<Duration>);
+
+impl Eta {
+    fn parts(&self) -> Option<(u64, u64, u64)> {
+        let d    = self.0?;
+        let secs = d.as_secs() + (d.subsec_nanos() > 0) as u64;
+
+        Some((secs / 3600, secs / 60 % 60, secs % 60))
+    }
+}
+
+impl Show for Eta {
+    fn len(&self) -> usize {
+        match self.parts() {
+            Some((0, _, _)) => 5,
+            Some((h, _, _)) => Show::len(&h) + 6,
+            None            => 5,
+        }
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        let (h, m, s) = match self.parts() {
+            Some(parts) => parts,
+            None        => return Show::write("--:--", w),
+        };
+
+        let hours = match h {
+            0 => 0,
+            _ => Show::write(&h, w)? + Show::write(&':', w)?,
+        };
+
+        Ok(hours + Format::write(&TWO_DIGITS, &m, w)? + Show::write(&':', w)? +
+            Format::write(&TWO_DIGITS, &s, w)?)
+    }
+}
+
+/// A progress bar, with the percentage done, the rate and the time left after it. It's shown
+/// `width` characters wide, however far along it is, unless the numbers after it need more room
+/// than that.
+#[derive(Clone, Debug)]
+pub struct ProgressBar {
+    total:   u64,
+    done:    u64,
+    elapsed: Duration,
+    start:   Instant,
+    width:   usize,
+    full:    char,
+    empty:   char,
+}
+
+impl ProgressBar {
+    /// A bar for `total` items, none done yet, starting the clock now.
+    pub fn new(total: u64) -> ProgressBar {
+        ProgressBar {
+            total,
+            done:    0,
+            elapsed: Duration::ZERO,
+            start:   Instant::now(),
+            width:   term::width().saturating_sub(1),
+            full:    '█',
+            empty:   '░',
+        }
+    }
+
+    /// How many characters wide the whole line is. The default is one less than the terminal's
+    /// width, since some terminals move to the next line after writing in the last column.
+    pub fn width(self, width: usize) -> ProgressBar {
+        ProgressBar { width, .. self }
+    }
+
+    /// The characters for the done and not yet done parts of the bar, by default `█` and `░`.
+    pub fn chars(self, full: char, empty: char) -> ProgressBar {
+        ProgressBar { full, empty, .. self }
+    }
+
+    /// Set how many items are done.
+    pub fn set(&mut self, done: u64) {
+        self.at(done, self.start.elapsed());
+    }
+
+    /// Count `n` more items as done.
+    pub fn inc(&mut self, n: u64) {
+        self.set(self.done.saturating_add(n));
+    }
+
+    /// Set how many items are done and how long they took, without looking at the clock.
+    pub fn at(&mut self, done: u64, elapsed: Duration) {
+        self.done    = done;
+        self.elapsed = elapsed;
+    }
+
+    pub fn done(&self) -> u64 { self.done }
+    pub fn total(&self) -> u64 { self.total }
+
+    /// Whether every item is done.
+    pub fn is_finished(&self) -> bool {
+        self.done >= self.total
+    }
+
+    // What fraction of `n` is done, rounded down. Nothing to do counts as all of it done.
+    fn share(&self, n: u64) -> u64 {
+        match self.total {
+            0 => n,
+            t => (u128::from(self.done.min(t)) * u128::from(n) / u128::from(t)) as u64,
+        }
+    }
+
+    fn rate(&self) -> Rate {
+        let secs = self.elapsed.as_secs_f64();
+
+        Rate(if secs > 0.0 { Some(self.done as f64 / secs) } else { None })
+    }
+
+    fn eta(&self) -> Eta {
+        let left = self.total.saturating_sub(self.done);
+
+        Eta(match (left, self.done) {
+            (0, _) => Some(Duration::ZERO),
+            (_, 0) => None,
+            _      => Some(self.elapsed.mul_f64(left as f64 / self.done as f64)),
+        })
+    }
+
+    fn lay_out<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let percent = self.share(100);
+        let (rate, eta) = (self.rate(), self.eta());
+
+        // " 100%  125.0/s ETA 00:00", with the rate and time left in fields of their own so the
+        // line doesn't jitter as they change.
+        let stats = 1 + 4 + 1 + Format::len(&right(8), &rate) + 5 + Format::len(&right(5), &eta);
+        let bar   = self.width.saturating_sub(2 + stats);
+        let full  = self.share(bar as u64) as usize;
+
+        Show::write(&'[', w)?;
+        Format::write(&Rep(full), &self.full, w)?;
+        Format::write(&Rep(bar - full), &self.empty, w)?;
+        Show::write(&']', w)?;
+        Show::write(&' ', w)?;
+        Format::write(&right(3), &percent, w)?;
+        Show::write("% ", w)?;
+        Format::write(&right(8), &rate, w)?;
+        Show::write(" ETA ", w)?;
+        Format::write(&right(5), &eta, w)?;
+
+        Ok(())
+    }
+}
+
+impl Show for ProgressBar {
+    fn len(&self) -> usize {
+        measure(|w| self.lay_out(w))
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| self.lay_out(w))
+    }
+}
+
+/// A spinner, for work that doesn't know how far along it is. It shows one frame at a time, and
+/// `tick` moves it on to the next.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use cats::progress::{Spinner, LINE};
+///
+/// # fn main() {
+/// let mut spinner = Spinner::new(LINE);
+/// let mut frames  = String::new();
+///
+/// for _ in 0..5 {
+///     frames += &scat!(spinner);
+///     spinner.tick();
+/// }
+///
+/// assert_eq!(frames, "|/-\\|");
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Spinner {
+    frames: &'static [char],
+    frame:  usize,
+}
+
+/// A spinner made of braille dots.
+pub const BRAILLE: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// A spinning line, for terminals without braille.
+pub const LINE: &[char] = &['|', '/', '-', '\\'];
+
+impl Spinner {
+    pub fn new(frames: &'static [char]) -> Spinner {
+        Spinner { frames, frame: 0 }
+    }
+
+    /// Move on to the next frame.
+    pub fn tick(&mut self) {
+        self.frame = (self.frame + 1) % self.frames.len().max(1);
+    }
+}
+
+impl Show for Spinner {
+    fn len(&self) -> usize {
+        self.frames.get(self.frame).map_or(0, |c| c.len_utf8())
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        match self.frames.get(self.frame) {
+            Some(c) => Show::write(c, w),
+            None    => Ok(0),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::cell::{Cell, RefCell};
+use std::io::{self, Write};
+use std::mem;
+use std::str;
+use std::thread::LocalKey;
+
+use style::{self, ColorLevel, Stream};
+
+type Slot = RefCell<Option<Box<dyn io::Write>>>;
+
+// Output held back by `set_buffered`, or `None` if this thread isn't buffering. Anything left is
+// written out when the thread exits, though Rust doesn't promise that for the main thread.
+struct Buffer(RefCell<Option<Vec<u8>>>);
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        if let Some(buf) = self.0.get_mut().take() {
+            let _ = write_unbuffered(&buf);
+        }
+    }
+}
+
+thread_local! {
+    static STDOUT: Slot   = const { RefCell::new(None) };
+    static STDERR: Slot   = const { RefCell::new(None) };
+    static BUFFER: Buffer = const { Buffer(RefCell::new(None)) };
+
+    static SCRATCH: Cell<Vec<u8>> = const { Cell::new(Vec::new()) };
+}
+
+// Buffered output is written out once there's this much of it.
+const BUFFER_LIMIT: usize = 8 * 1024;
+
+// A scratch buffer that's grown past this is freed rather than kept for the next cat.
+const SCRATCH_LIMIT: usize = 64 * 1024;
+
+/// Send the output of `cat!`, `catln!` and their `try_` forms on this thread to `sink` instead of
+/// stdout, or back to stdout if `sink` is `None`. Returns the sink that was set before.
+///
+/// This is meant for tests that want to check what was printed, and for programs that show their
+/// output somewhere other than a terminal.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// use std::cell::RefCell;
+/// use std::io::{self, Write};
+/// use std::rc::Rc;
+///
+/// struct Capture(Rc<RefCell<Vec<u8>>>);
+///
+/// impl Write for Capture {
+///     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+///         self.0.borrow_mut().extend_from_slice(buf);
+///         Ok(buf.len())
+///     }
+///
+///     fn flush(&mut self) -> io::Result<()> { Ok(()) }
+/// }
+///
+/// # fn main() {
+/// let out = Rc::new(RefCell::new(Vec::new()));
+///
+/// cats::set_stdout(Some(Box::new(Capture(out.clone()))));
+/// catln!("Meow, ", 42);
+/// cats::set_stdout(None);
+///
+/// assert_eq!(*out.borrow(), b"Meow, 42\n");
+/// # }
+/// ```
+pub fn set_stdout(sink: Option<Box<dyn io::Write>>) -> Option<Box<dyn io::Write>> {
+    STDOUT.with(|slot| mem::replace(&mut *slot.borrow_mut(), sink))
+}
+
+/// Like `set_stdout`, but for `ecat!` and `ecatln!`.
+pub fn set_stderr(sink: Option<Box<dyn io::Write>>) -> Option<Box<dyn io::Write>> {
+    STDERR.with(|slot| mem::replace(&mut *slot.borrow_mut(), sink))
+}
+
+/// Turn buffering of `cat!` and `catln!` on this thread on or off.
+///
+/// While it's on, their output collects in memory and is only written out when 8 KiB has built up,
+/// when `catflush!()` is called, or when the thread exits. That saves a system call per line for
+/// programs that print a lot of small lines, but since the main thread isn't guaranteed to run
+/// its exit code, call `catflush!()` before returning from `main`. Turning buffering off flushes
+/// what's been held back.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// cats::set_buffered(true).unwrap();
+///
+/// for i in 0..1000 {
+///     catln!("line ", i);
+/// }
+///
+/// catflush!();
+/// # }
+/// ```
+pub fn set_buffered(buffered: bool) -> io::Result<()> {
+    let old = BUFFER.with(|b| {
+        let mut b = b.0.borrow_mut();
+
+        if buffered {
+            b.get_or_insert_with(Vec::new);
+            None
+        } else {
+            b.take()
+        }
+    });
+
+    match old {
+        Some(buf) => write_unbuffered(&buf),
+        None      => Ok(()),
+    }
+}
+
+// Write straight to the stdout sink, bypassing the buffer, and flush it.
+fn write_unbuffered(buf: &[u8]) -> io::Result<()> {
+    let mut sink = Sink::new(&STDOUT, Stream::Stdout, || Target::Stdout(io::stdout().lock()));
+
+    sink.write_all(buf)?;
+    sink.flush()
+}
+
+#[doc(hidden)]
+pub fn __flush_stdout() -> io::Result<()> {
+    let buf = BUFFER.try_with(|b| b.0.borrow_mut().as_mut().map(mem::take)).ok().and_then(|b| b);
+
+    write_unbuffered(&buf.unwrap_or_default())
+}
+
+// Appends to this thread's buffer.
+struct Buffered;
+
+impl io::Write for Buffered {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let full = BUFFER.with(|b| match *b.0.borrow_mut() {
+            Some(ref mut v) => { v.extend_from_slice(buf); Some(v.len() >= BUFFER_LIMIT) },
+            None            => None,
+        });
+
+        match full {
+            Some(true)  => __flush_stdout()?,
+            Some(false) => (),
+            // Buffering was turned off partway through a cat.
+            None        => write_unbuffered(buf)?,
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { __flush_stdout() }
+}
+
+enum Target {
+    Buffered(Buffered),
+    Custom(Box<dyn io::Write>),
+    Stdout(io::StdoutLock<'static>),
+    Stderr(io::StderrLock<'static>),
+}
+
+// Where a single cat to stdout or stderr goes. A sink set with `set_stdout` or `set_stderr` is
+// taken out of its slot for the duration of the cat and put back when this is dropped, and the
+// real stdout or stderr is locked, so either way the cat can't be interleaved with other output.
+// While it's alive, styles go by the color level of the stream it writes to, as it was when the
+// cat started.
+#[doc(hidden)]
+pub struct Sink {
+    slot:   &'static LocalKey<Slot>,
+    target: Option<Target>,
+    outer:  Option<ColorLevel>,
+}
+
+impl Sink {
+    fn new(slot: &'static LocalKey<Slot>, stream: Stream, default: fn() -> Target) -> Sink {
+        // If the thread is being torn down the slot may already be gone, in which case there's
+        // nothing to redirect to.
+        let custom = slot.try_with(|s| s.borrow_mut().take()).ok().and_then(|s| s);
+        let stream = if custom.is_some() { Stream::Other } else { stream };
+
+        Sink::with_target(slot, stream, custom.map_or_else(default, Target::Custom))
+    }
+
+    fn with_target(slot: &'static LocalKey<Slot>, stream: Stream, target: Target) -> Sink {
+        Sink { slot, target: Some(target), outer: style::enter_stream(stream) }
+    }
+
+    fn target(&mut self) -> &mut dyn io::Write {
+        match self.target {
+            Some(Target::Buffered(ref mut w)) => w,
+            Some(Target::Custom(ref mut w))   => w,
+            Some(Target::Stdout(ref mut w))   => w,
+            Some(Target::Stderr(ref mut w))   => w,
+            None                              => unreachable!(),
+        }
+    }
+}
+
+impl io::Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> { self.target().write(buf) }
+
+    fn flush(&mut self) -> io::Result<()> { self.target().flush() }
+}
+
+impl Drop for Sink {
+    fn drop(&mut self) {
+        style::leave_stream(self.outer);
+
+        if let Some(Target::Custom(w)) = self.target.take() {
+            // Unless a new sink was set in the meantime, put this one back.
+            let _ = self.slot.try_with(|s| {
+                let mut s = s.borrow_mut();
+
+                if s.is_none() {
+                    *s = Some(w);
+                }
+            });
+        }
+    }
+}
+
+#[doc(hidden)]
+pub fn __stdout() -> Sink {
+    if BUFFER.try_with(|b| b.0.borrow().is_some()).unwrap_or(false) {
+        return Sink::with_target(&STDOUT, Stream::Stdout, Target::Buffered(Buffered));
+    }
+
+    Sink::new(&STDOUT, Stream::Stdout, || Target::Stdout(io::stdout().lock()))
+}
+
+#[doc(hidden)]
+pub fn __stderr() -> Sink {
+    Sink::new(&STDERR, Stream::Stderr, || Target::Stderr(io::stderr().lock()))
+}
+
+// This thread's scratch buffer, for macros which need a cat as a `&str` but don't keep it, like the
+// logging ones. It's taken out of its slot while in use, so a cat nested inside another one just
+// gets a fresh buffer, and it's put back cleared when dropped.
+#[doc(hidden)]
+pub struct __Scratch(Vec<u8>);
+
+impl __Scratch {
+    pub fn take() -> __Scratch {
+        __Scratch(SCRATCH.try_with(Cell::take).unwrap_or_default())
+    }
+
+    pub fn buf(&mut self) -> &mut Vec<u8> { &mut self.0 }
+
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(&self.0).expect("cat generated invalid utf-8")
+    }
+}
+
+impl Drop for __Scratch {
+    fn drop(&mut self) {
+        if self.0.capacity() <= SCRATCH_LIMIT {
+            let mut buf = mem::take(&mut self.0);
+
+            buf.clear();
+            let _ = SCRATCH.try_with(|s| s.set(buf));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::str;
+
+/// The error when a cat doesn't fit in the buffer given to `scat_into_slice!` or a `SliceWriter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferFull;
+
+impl fmt::Display for BufferFull {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str("buffer full") }
+}
+
+impl error::Error for BufferFull {}
+
+// Whether `e` is a `SliceWriter` running out of room, rather than an argument of the cat failing.
+// An argument writing into memory never returns `WriteZero` by itself.
+#[doc(hidden)]
+pub fn __is_buffer_full(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::WriteZero
+}
+
+/// Writes into a caller-provided byte buffer, with no allocation, even when it runs out of room. A
+/// write that doesn't fit is refused whole, with a bare `io::ErrorKind::WriteZero` error. The
+/// writes before it are kept, though, and an argument of a cat may take more than one, as a padded
+/// one does, so the buffer can end partway through an argument.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Pad, SliceWriter, Width};
+/// # use std::io;
+/// # fn main() {
+/// let mut buf = [0u8; 16];
+/// let mut w   = SliceWriter::new(&mut buf);
+///
+/// fcat!(w, "lives=", 9).unwrap();
+///
+/// assert_eq!(w.written(), b"lives=9");
+/// assert_eq!(fcat!(w, "; a name too long to fit").unwrap_err().kind(), io::ErrorKind::WriteZero);
+/// assert_eq!(w.written(), b"lives=9");
+///
+/// // Only some of the padding after "abc" fits, and what did is kept.
+/// assert!(cat_write!(&mut w, Pad::from(Width::new(10)); "abc").is_err());
+/// assert_eq!(w.written(), b"lives=9abc      ");
+/// # }
+/// ```
+pub struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceWriter<'a> {
+    pub fn new(buf: &'a mut [u8]) -> SliceWriter<'a> {
+        SliceWriter { buf, len: 0 }
+    }
+
+    /// The bytes written so far.
+    pub fn written(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// How many more bytes fit.
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    /// The bytes written so far, as a `str` borrowed from the buffer.
+    ///
+    /// # Panics
+    /// If they aren't valid utf-8, which can only happen if a `Show` impl broke its promise.
+    pub fn into_str(self) -> &'a str {
+        let buf: &'a [u8] = self.buf;
+
+        str::from_utf8(&buf[..self.len]).expect("scat_into_slice! macro generated invalid utf-8")
+    }
+}
+
+impl<'a> io::Write for SliceWriter<'a> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.len() > self.remaining() {
+            return Err(io::ErrorKind::WriteZero.into());
+        }
+
+        self.buf[self.len..self.len + data.len()].copy_from_slice(data);
+        self.len += data.len();
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
@@ -0,0 +1,184 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+use std::str;
+
+use traits::Show;
+
+// Strings up to this many bytes are kept inline.
+const INLINE_CAP: usize = 24;
+
+#[derive(Clone)]
+enum Repr {
+    Inline(u8, [u8; INLINE_CAP]),
+    Heap(String),
+}
+
+/// A string that keeps up to 24 bytes inline and only moves to the heap when it's longer. This is
+/// what `scat_small!` returns, so that short keys and labels built in a hot loop don't allocate.
+///
+/// It derefs to `str`, and `into_string` turns it into a `String`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let key = scat_small!("cat:", 42);
+///
+/// assert_eq!(key, "cat:42");
+/// assert!(key.is_inline());
+///
+/// let long = scat_small!("a much longer label than fits inline: ", 42);
+///
+/// assert!(!long.is_inline());
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct CatString(Repr);
+
+impl CatString {
+    pub fn new() -> CatString {
+        CatString(Repr::Inline(0, [0; INLINE_CAP]))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self.0 {
+            // Only valid utf-8 is ever stored inline.
+            Repr::Inline(len, ref buf) => unsafe { str::from_utf8_unchecked(&buf[..len as usize]) },
+            Repr::Heap(ref s)          => s,
+        }
+    }
+
+    /// Is the string stored inline, rather than on the heap?
+    pub fn is_inline(&self) -> bool {
+        match self.0 {
+            Repr::Inline(..) => true,
+            Repr::Heap(_)    => false,
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        match self.0 {
+            Repr::Inline(..) => String::from(self.as_str()),
+            Repr::Heap(s)    => s,
+        }
+    }
+}
+
+impl Default for CatString {
+    fn default() -> CatString { CatString::new() }
+}
+
+impl Deref for CatString {
+    type Target = str;
+
+    fn deref(&self) -> &str { self.as_str() }
+}
+
+impl AsRef<str> for CatString {
+    fn as_ref(&self) -> &str { self.as_str() }
+}
+
+impl fmt::Display for CatString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Display::fmt(self.as_str(), f) }
+}
+
+impl fmt::Debug for CatString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { fmt::Debug::fmt(self.as_str(), f) }
+}
+
+impl PartialEq for CatString {
+    fn eq(&self, other: &CatString) -> bool { self.as_str() == other.as_str() }
+}
+
+impl Eq for CatString {}
+
+impl PartialEq<str> for CatString {
+    fn eq(&self, other: &str) -> bool { self.as_str() == other }
+}
+
+impl<'a> PartialEq<&'a str> for CatString {
+    fn eq(&self, other: &&'a str) -> bool { self.as_str() == *other }
+}
+
+impl PartialEq<String> for CatString {
+    fn eq(&self, other: &String) -> bool { self.as_str() == other }
+}
+
+impl Show for CatString {
+    fn len(&self) -> usize { self.as_str().len() }
+    fn stored_bytes(&self) -> Option<&[u8]> { Some(self.as_str().as_bytes()) }
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Show::write(self.as_str(), w)
+    }
+}
+
+// Where `scat_small!` writes its cat. It starts out inline, and spills to the heap if the cat turns
+// out to be longer than the inline buffer, or is known to be from the start.
+#[doc(hidden)]
+pub struct __CatBuf {
+    len:    usize,
+    inline: [u8; INLINE_CAP],
+    heap:   Option<Vec<u8>>,
+}
+
+impl __CatBuf {
+    pub fn new(hint: usize) -> __CatBuf {
+        let heap = if hint > INLINE_CAP { Some(Vec::with_capacity(hint)) } else { None };
+
+        __CatBuf { len: 0, inline: [0; INLINE_CAP], heap }
+    }
+
+    pub fn finish(self) -> CatString {
+        let repr = match self.heap {
+            Some(v) => String::from_utf8(v).ok().map(Repr::Heap),
+            None    => str::from_utf8(&self.inline[..self.len]).ok()
+                           .map(|_| Repr::Inline(self.len as u8, self.inline)),
+        };
+
+        match repr {
+            Some(repr) => CatString(repr),
+            None       => panic!("scat_small! macro generated invalid utf-8"),
+        }
+    }
+}
+
+impl io::Write for __CatBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.heap.is_none() && self.len + buf.len() > INLINE_CAP {
+            let mut v = Vec::with_capacity(self.len + buf.len());
+
+            v.extend_from_slice(&self.inline[..self.len]);
+            self.heap = Some(v);
+        }
+
+        match self.heap {
+            Some(ref mut v) => v.extend_from_slice(buf),
+            None            => {
+                self.inline[self.len..self.len + buf.len()].copy_from_slice(buf);
+                self.len += buf.len();
+            },
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
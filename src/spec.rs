@@ -0,0 +1,234 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::error;
+use std::fmt;
+use std::io;
+
+use traits::{Show, Format, Rep};
+
+/// Where a value sits within a field that's wider than it is.
+#[derive(Clone, Copy)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+/// What happens when a value is wider than the field it's meant to fill.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Pad, Align, Overflow, FormattedInt};
+/// # use std::io;
+/// # fn main() {
+/// const NAME: Pad = Pad { width: 4, fill: ' ', align: Align::Left, overflow: Overflow::Truncate };
+///
+/// assert_eq!(scat!('|', NAME; "Tom", '|', NAME; "Mittens", '|'), "|Tom |Mitt|");
+///
+/// // Numbers keep their low-order digits.
+/// let year = FormattedInt::decimal().min_len(2).overflow(Overflow::Truncate);
+///
+/// assert_eq!(scat!(year; 1987), "87");
+///
+/// let strict  = Pad { overflow: Overflow::Error, .. NAME };
+/// let mut out = Vec::new();
+///
+/// let err = cat_write!(out, strict; "Mittens").unwrap_err();
+///
+/// assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Let the field grow to fit the value. This is the default.
+    Expand,
+
+    /// Cut the value down to the field's width. Strings and other values keep their first
+    /// characters, and integers their low-order digits.
+    Truncate,
+
+    /// Fail the write with an `io::ErrorKind::InvalidInput` error wrapping `FieldOverflow`. The
+    /// cats into memory that don't return a `Result`, such as `scat!`, `vcat!` and
+    /// `Show::to_cat_string`, panic with it instead, and `try_scat!` returns it in a `ScatError`.
+    Error,
+}
+
+/// The error when a value doesn't fit in a field whose `Overflow` is `Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldOverflow {
+    /// The width of the field, in characters.
+    pub width: usize,
+
+    /// How many characters the value needed.
+    pub chars: usize,
+}
+
+impl fmt::Display for FieldOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "value {} characters wide doesn't fit in a field of {}", self.chars, self.width)
+    }
+}
+
+impl error::Error for FieldOverflow {}
+
+impl From<FieldOverflow> for io::Error {
+    fn from(e: FieldOverflow) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+// Passes on only the first `chars` characters written to it, and counts the bytes it passed on.
+struct Truncated<'a, W: io::Write + 'a> {
+    w:       &'a mut W,
+    chars:   usize,
+    written: usize,
+}
+
+impl<'a, W: io::Write> io::Write for Truncated<'a, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Everything up to the first byte that would start a character past the limit.
+        let mut end = buf.len();
+
+        for (i, &b) in buf.iter().enumerate() {
+            if b & 0xc0 != 0x80 {
+                if self.chars == 0 {
+                    end = i;
+                    break;
+                }
+
+                self.chars -= 1;
+            }
+        }
+
+        self.w.write_all(&buf[..end])?;
+        self.written += end;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.w.flush() }
+}
+
+// Write the first `chars` characters of `t`.
+fn write_truncated<T, W>(t: &T, chars: usize, w: &mut W) -> io::Result<usize>
+where T: Show, W: io::Write {
+    let mut truncated = Truncated { w, chars, written: 0 };
+
+    Show::write(t, &mut truncated)?;
+
+    Ok(truncated.written)
+}
+
+/// A minimum field width in characters, along with the fill character and alignment used to reach
+/// it. This is the part of a format spec shared by `Pad`, `FormattedStr` and `FormattedInt`, and it
+/// converts to and from `Pad` directly.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Width, Align, Pad, FormattedInt};
+/// # fn main() {
+/// let w = Width::new(6).fill('.').align(Align::Right);
+///
+/// assert_eq!(scat!(Pad::from(w); "ab"), "....ab");
+/// assert_eq!(scat!(FormattedInt::decimal().width(w); 42), "....42");
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct Width {
+    pub min:      usize,
+    pub fill:     char,
+    pub align:    Align,
+    pub overflow: Overflow,
+}
+
+impl Width {
+    /// Left-aligned, filled with spaces and growing to fit, which is how `std::fmt` treats a bare
+    /// width.
+    pub const fn new(min: usize) -> Width {
+        Width { min, fill: ' ', align: Align::Left, overflow: Overflow::Expand }
+    }
+
+    pub const fn fill(self, fill: char) -> Width {
+        Width { fill, .. self }
+    }
+
+    pub const fn align(self, align: Align) -> Width {
+        Width { align, .. self }
+    }
+
+    pub const fn overflow(self, overflow: Overflow) -> Width {
+        Width { overflow, .. self }
+    }
+
+    // How many fill characters go before and after a value `chars` characters wide.
+    pub(crate) fn padding(&self, chars: usize) -> (usize, usize) {
+        let total = self.min.saturating_sub(chars);
+
+        match self.align {
+            Align::Left   => (0, total),
+            Align::Right  => (total, 0),
+            Align::Center => (total / 2, total - total / 2),
+        }
+    }
+
+    // Write `t` with its padding, given how many characters wide it is.
+    pub(crate) fn write<T, W>(&self, t: &T, chars: usize, w: &mut W) -> io::Result<usize>
+    where T: Show, W: io::Write {
+        if chars > self.min {
+            match self.overflow {
+                Overflow::Expand   => (),
+                Overflow::Truncate => return write_truncated(t, self.min, w),
+                Overflow::Error    => return Err(FieldOverflow { width: self.min, chars }.into()),
+            }
+        }
+
+        let (before, after) = self.padding(chars);
+
+        Ok(Format::write(&Rep(before), &self.fill, w)? +
+            Show::write(t, w)? +
+            Format::write(&Rep(after), &self.fill, w)?)
+    }
+
+    // The length in bytes of `t` with its padding.
+    pub(crate) fn len<T: Show>(&self, t: &T, chars: usize) -> usize {
+        if chars > self.min && self.overflow == Overflow::Truncate {
+            return write_truncated(t, self.min, &mut io::sink()).unwrap_or(0);
+        }
+
+        let (before, after) = self.padding(chars);
+
+        Show::len(t) + (before + after) * self.fill.len_utf8()
+    }
+}
+
+/// A limit on how much of a value is shown, like the `.N` in a format spec. For strings it's the
+/// most characters shown.
+#[derive(Clone, Copy)]
+pub struct Precision(pub usize);
+
+impl Precision {
+    // The longest prefix of `s` that fits.
+    pub(crate) fn truncate<'a>(&self, s: &'a str) -> &'a str {
+        match s.char_indices().nth(self.0) {
+            Some((idx, _)) => &s[..idx],
+            None           => s,
+        }
+    }
+}
@@ -0,0 +1,430 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! Colors and text styles for terminals, as ANSI escape codes.
+//!
+//! A `Style` is a format, so `cat!(ERROR; "failed")` shows "failed" in bold red. What it writes
+//! depends on where the cat is going. Each of stdout and stderr gets a `ColorLevel` when first
+//! used:
+//!
+//! - `None` if `NO_COLOR` is set, if the stream isn't a terminal or if `TERM` is `dumb`.
+//! - `TrueColor` if `COLORTERM` is `truecolor` or `24bit`.
+//! - `Ansi256` if `TERM` mentions `256color`.
+//! - `Basic` otherwise.
+//!
+//! A color the level can't show is swapped for the nearest one it can, and at `None` nothing but
+//! the value itself is written, so piping a program's output to a file leaves it free of escape
+//! codes. `cat!` and `catln!` go by stdout's level, `ecat!` and `ecatln!` by stderr's, a sink set
+//! with `set_stdout` or `set_stderr` counts as not a terminal, and anything else, like `scat!`,
+//! goes by stdout's. `set_color_level` overrides all of that, as for a `--color` option.
+//!
+//! Styles go outside of padding, since the escape codes would otherwise count towards the width.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::style::{self, Color, ColorLevel, Style, ERROR};
+//!
+//! # fn main() {
+//! style::set_color_level(Some(ColorLevel::TrueColor));
+//!
+//! assert_eq!(scat!(ERROR; "failed"), "\x1b[1;31mfailed\x1b[0m");
+//!
+//! let orange = Style::new().fg(Color::Rgb(255, 135, 0)).underline();
+//!
+//! assert_eq!(scat!(orange; 42), "\x1b[4;38;2;255;135;0m42\x1b[0m");
+//!
+//! style::set_color_level(Some(ColorLevel::Ansi256));
+//! assert_eq!(scat!(orange; 42), "\x1b[4;38;5;208m42\x1b[0m");
+//!
+//! style::set_color_level(Some(ColorLevel::Basic));
+//! assert_eq!(scat!(orange; 42), "\x1b[4;33m42\x1b[0m");
+//!
+//! style::set_color_level(Some(ColorLevel::None));
+//! assert_eq!(scat!(orange; 42), "42");
+//! # }
+//! ```
+
+use std::cell::Cell;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use traits::{Show, Format, Formatted, measure};
+
+/// How many colors the place a cat is going can show.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorLevel {
+    /// No escape codes at all.
+    None,
+    /// The 16 standard colors.
+    Basic,
+    /// The 256-color palette.
+    Ansi256,
+    /// Any RGB color.
+    TrueColor,
+}
+
+const LEVELS: [ColorLevel; 4] = [ColorLevel::None, ColorLevel::Basic, ColorLevel::Ansi256,
+                                 ColorLevel::TrueColor];
+
+// The level set with `set_color_level`, as one more than its index in `LEVELS`, or zero to go by
+// the streams.
+static OVERRIDE: AtomicUsize = AtomicUsize::new(0);
+
+static STDOUT_LEVEL: OnceLock<ColorLevel> = OnceLock::new();
+static STDERR_LEVEL: OnceLock<ColorLevel> = OnceLock::new();
+
+// Where the cat that's being written on this thread is going.
+#[derive(Clone, Copy)]
+pub(crate) enum Stream {
+    Stdout,
+    Stderr,
+    Other,
+}
+
+thread_local! {
+    // The level for the cat to stdout or stderr being written on this thread, looked up once when
+    // it starts so that measuring and writing it agree even if `set_color_level` is called on
+    // another thread in between.
+    static LEVEL: Cell<Option<ColorLevel>> = const { Cell::new(None) };
+}
+
+// Note that this thread's cats are going to `stream`, and return the level they went by before,
+// to be put back with `leave_stream`.
+pub(crate) fn enter_stream(stream: Stream) -> Option<ColorLevel> {
+    let level = stream_level(Some(stream));
+
+    LEVEL.try_with(|l| l.replace(Some(level))).ok().and_then(|l| l)
+}
+
+pub(crate) fn leave_stream(outer: Option<ColorLevel>) {
+    let _ = LEVEL.try_with(|l| l.set(outer));
+}
+
+fn detect(is_terminal: bool) -> ColorLevel {
+    let var  = |name| env::var(name).unwrap_or_default();
+    let term = var("TERM");
+
+    if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) || !is_terminal || term == "dumb" {
+        ColorLevel::None
+    } else if matches!(&var("COLORTERM")[..], "truecolor" | "24bit") {
+        ColorLevel::TrueColor
+    } else if term.contains("256color") {
+        ColorLevel::Ansi256
+    } else {
+        ColorLevel::Basic
+    }
+}
+
+/// Use `level` for every cat instead of going by the streams, or go back to the streams with
+/// `None`.
+///
+/// A cat to stdout or stderr that's already under way on another thread keeps the level it started
+/// with. Any other cat, like `scat!`, looks the level up each time it measures or writes a styled
+/// value, so one under way on another thread can come out a different length than it measured.
+/// Set the level before other threads start those.
+pub fn set_color_level(level: Option<ColorLevel>) {
+    let index = level.map_or(0, |l| LEVELS.iter().position(|&x| x == l).unwrap_or(0) + 1);
+
+    OVERRIDE.store(index, Ordering::Relaxed);
+}
+
+/// The color level for a cat on this thread right now.
+pub fn color_level() -> ColorLevel {
+    match LEVEL.try_with(Cell::get).ok().and_then(|l| l) {
+        Some(level) => level,
+        None        => stream_level(None),
+    }
+}
+
+// The level for a cat to `stream`, where no stream means stdout.
+fn stream_level(stream: Option<Stream>) -> ColorLevel {
+    if let Some(i) = OVERRIDE.load(Ordering::Relaxed).checked_sub(1) {
+        return LEVELS[i];
+    }
+
+    match stream {
+        Some(Stream::Stderr) => *STDERR_LEVEL.get_or_init(|| detect(io::stderr().is_terminal())),
+        Some(Stream::Other)  => ColorLevel::None,
+        _                    => *STDOUT_LEVEL.get_or_init(|| detect(io::stdout().is_terminal())),
+    }
+}
+
+/// A terminal color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// A color from the 256-color palette.
+    Fixed(u8),
+    /// Any color, for terminals that can show it.
+    Rgb(u8, u8, u8),
+}
+
+// The basic colors, in palette order, and roughly how xterm shows them.
+const BASIC: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black,         (0, 0, 0)),
+    (Color::Red,           (205, 0, 0)),
+    (Color::Green,         (0, 205, 0)),
+    (Color::Yellow,        (205, 205, 0)),
+    (Color::Blue,          (0, 0, 238)),
+    (Color::Magenta,       (205, 0, 205)),
+    (Color::Cyan,          (0, 205, 205)),
+    (Color::White,         (229, 229, 229)),
+    (Color::BrightBlack,   (127, 127, 127)),
+    (Color::BrightRed,     (255, 0, 0)),
+    (Color::BrightGreen,   (0, 255, 0)),
+    (Color::BrightYellow,  (255, 255, 0)),
+    (Color::BrightBlue,    (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan,    (0, 255, 255)),
+    (Color::BrightWhite,   (255, 255, 255)),
+];
+
+// The levels of each channel in the palette's 6x6x6 color cube.
+const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).pow(2) as u32;
+
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+// The index of the entry in `CUBE` closest to `x`.
+fn cube_index(x: u8) -> u8 {
+    (0..6).min_by_key(|&i| (i32::from(CUBE[i]) - i32::from(x)).abs()).unwrap_or(0) as u8
+}
+
+impl Color {
+    fn basic_index(self) -> Option<u8> {
+        BASIC.iter().position(|&(c, _)| c == self).map(|i| i as u8)
+    }
+
+    fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Rgb(r, g, b)       => (r, g, b),
+            Color::Fixed(n) if n < 16 => BASIC[n as usize].1,
+            Color::Fixed(n) if n < 232 => {
+                let n = n - 16;
+
+                (CUBE[(n / 36) as usize], CUBE[(n / 6 % 6) as usize], CUBE[(n % 6) as usize])
+            },
+            Color::Fixed(n)           => { let v = 8 + 10 * (n - 232); (v, v, v) },
+            basic                     => BASIC[basic.basic_index().unwrap_or(0) as usize].1,
+        }
+    }
+
+    // The nearest color in the 256-color palette.
+    fn to_ansi256(self) -> u8 {
+        let (r, g, b) = match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Fixed(n)     => return n,
+            basic               => return basic.basic_index().unwrap_or(0),
+        };
+
+        let cube = (cube_index(r), cube_index(g), cube_index(b));
+        let gray = ((u32::from(r) + u32::from(g) + u32::from(b)) / 3).saturating_sub(3) / 10;
+        let gray = gray.min(23) as u8;
+
+        let cube_rgb = (CUBE[cube.0 as usize], CUBE[cube.1 as usize], CUBE[cube.2 as usize]);
+        let gray_rgb = (8 + 10 * gray, 8 + 10 * gray, 8 + 10 * gray);
+
+        if distance((r, g, b), gray_rgb) < distance((r, g, b), cube_rgb) {
+            232 + gray
+        } else {
+            16 + 36 * cube.0 + 6 * cube.1 + cube.2
+        }
+    }
+
+    // The nearest of the 16 basic colors, as its index.
+    fn to_basic(self) -> u8 {
+        if let Some(i) = self.basic_index() {
+            return i;
+        }
+
+        let rgb = self.rgb();
+
+        (0..16).min_by_key(|&i| distance(rgb, BASIC[i].1)).unwrap_or(0) as u8
+    }
+
+    // Write the SGR parameters for this as a foreground color, or a background one if `bg`,
+    // at `level`, which isn't `None`.
+    fn write_code<W: io::Write>(self, level: ColorLevel, bg: bool, w: &mut W) -> io::Result<usize> {
+        let base = if bg { 40 } else { 30 };
+
+        match (level, self) {
+            (ColorLevel::TrueColor, Color::Rgb(r, g, b)) => {
+                Ok(Show::write(&(base + 8), w)? + Show::write(";2;", w)? + Show::write(&r, w)? +
+                    Show::write(&';', w)? + Show::write(&g, w)? + Show::write(&';', w)? +
+                    Show::write(&b, w)?)
+            },
+            (ColorLevel::TrueColor, c) | (ColorLevel::Ansi256, c) if c.basic_index().is_none() => {
+                Ok(Show::write(&(base + 8), w)? + Show::write(";5;", w)? +
+                    Show::write(&c.to_ansi256(), w)?)
+            },
+            (_, c) => {
+                let i = c.to_basic();
+
+                Show::write(&(if i < 8 { base + i } else { base + 60 + i - 8 }), w)
+            },
+        }
+    }
+}
+
+const BOLD:          u8 = 1 << 0;
+const DIM:           u8 = 1 << 1;
+const ITALIC:        u8 = 1 << 2;
+const UNDERLINE:     u8 = 1 << 3;
+const INVERSE:       u8 = 1 << 4;
+const STRIKETHROUGH: u8 = 1 << 5;
+
+// Each attribute and its SGR code.
+const ATTRS: [(u8, u8); 6] = [(BOLD, 1), (DIM, 2), (ITALIC, 3), (UNDERLINE, 4), (INVERSE, 7),
+                              (STRIKETHROUGH, 9)];
+
+/// Colors and attributes to show a value with. Showing a value with a `Style` surrounds it with
+/// the escape codes to turn the style on and back off, as far as the current `ColorLevel` allows.
+/// Every method is a `const fn`, so named styles can be constants, like the ones in this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    fg:    Option<Color>,
+    bg:    Option<Color>,
+    attrs: u8,
+}
+
+impl Style {
+    /// No colors or attributes.
+    pub const fn new() -> Style {
+        Style { fg: None, bg: None, attrs: 0 }
+    }
+
+    pub const fn fg(self, color: Color) -> Style {
+        Style { fg: Some(color), .. self }
+    }
+
+    pub const fn bg(self, color: Color) -> Style {
+        Style { bg: Some(color), .. self }
+    }
+
+    const fn attr(self, attr: u8) -> Style {
+        Style { attrs: self.attrs | attr, .. self }
+    }
+
+    pub const fn bold(self) -> Style { self.attr(BOLD) }
+    pub const fn dim(self) -> Style { self.attr(DIM) }
+    pub const fn italic(self) -> Style { self.attr(ITALIC) }
+    pub const fn underline(self) -> Style { self.attr(UNDERLINE) }
+    pub const fn inverse(self) -> Style { self.attr(INVERSE) }
+    pub const fn strikethrough(self) -> Style { self.attr(STRIKETHROUGH) }
+
+    fn is_plain(&self) -> bool {
+        *self == Style::new()
+    }
+
+    // Write the code that turns the style on.
+    fn write_start<W: io::Write>(&self, level: ColorLevel, w: &mut W) -> io::Result<usize> {
+        let mut written = Show::write("\x1b[", w)?;
+        let mut first   = true;
+        let mut sep     = |w: &mut W| -> io::Result<usize> {
+            if first { first = false; Ok(0) } else { Show::write(&';', w) }
+        };
+
+        for &(attr, code) in &ATTRS {
+            if self.attrs & attr != 0 {
+                written += sep(w)? + Show::write(&code, w)?;
+            }
+        }
+
+        if let Some(fg) = self.fg {
+            written += sep(w)? + fg.write_code(level, false, w)?;
+        }
+
+        if let Some(bg) = self.bg {
+            written += sep(w)? + bg.write_code(level, true, w)?;
+        }
+
+        Ok(written + Show::write(&'m', w)?)
+    }
+
+    // The level to show a value at with this style, or `None` if it's shown as it is.
+    fn level(&self) -> Option<ColorLevel> {
+        match color_level() {
+            _ if self.is_plain() => None,
+            ColorLevel::None     => None,
+            level                => Some(level),
+        }
+    }
+}
+
+// The code that turns every style back off.
+const RESET: &str = "\x1b[0m";
+
+impl<T> Format<T> for Style
+where T: Show {
+    fn len(&self, t: &T) -> usize {
+        let level = match self.level() {
+            Some(level) => level,
+            None        => return t.len(),
+        };
+
+        let start = measure(|w| self.write_start(level, w));
+
+        start.saturating_add(t.len()).saturating_add(RESET.len())
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let level = match self.level() {
+            Some(level) => level,
+            None        => return t.write(w),
+        };
+
+        Ok(self.write_start(level, w)? + t.write(w)? + Show::write(RESET, w)?)
+    }
+}
+
+/// A value with the `Style` to show it in.
+pub type Styled<'x, T> = Formatted<'x, Style, T>;
+
+/// For errors: bold red.
+pub const ERROR: Style = Style::new().fg(Color::Red).bold();
+
+/// For warnings: bold yellow.
+pub const WARNING: Style = Style::new().fg(Color::Yellow).bold();
+
+/// For things that went well: green.
+pub const SUCCESS: Style = Style::new().fg(Color::Green);
+
+/// For notes and information: cyan.
+pub const INFO: Style = Style::new().fg(Color::Cyan);
+
+/// For hints and things of less interest: dim.
+pub const HINT: Style = Style::new().dim();
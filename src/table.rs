@@ -0,0 +1,427 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Tables whose columns are as wide as their widest cell.
+//!
+//! The `table!` macro lays out rows of cats in one go. A `Table` is built up a row at a time
+//! instead, from any `Show` values, and adds headers, per-column alignment, borders and wrapping.
+//! It's a `Show` itself, so it's printed with `catln!`, `cat_write!` and the rest.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::{Align, Formatted, HEX};
+//! use cats::table::{Border, Table};
+//!
+//! # fn main() {
+//! let mut table = Table::new()
+//!     .header(&[&"Name", &"Lives", &"Id"])
+//!     .align(1, Align::Right)
+//!     .border(Border::Light);
+//!
+//! table.row(&[&"Tom", &9, &Formatted(HEX, &255u8)]);
+//! table.row(&[&"Mittens", &3, &Formatted(HEX, &10u8)]);
+//!
+//! assert_eq!(scat!(table), "\
+//! ┌─────────┬───────┬────┐
+//! │ Name    │ Lives │ Id │
+//! ├─────────┼───────┼────┤
+//! │ Tom     │     9 │ ff │
+//! │ Mittens │     3 │ a  │
+//! └─────────┴───────┴────┘
+//! ");
+//! # }
+//! ```
+
+use std::io;
+
+use error::__expect_written;
+use spec::{Align, Width};
+use text::Wrap;
+use traits::{Show, ShowDyn, Format, Rep, measure, write_counted};
+
+fn chars(cell: &[u8]) -> usize {
+    cell.iter().filter(|&&b| b & 0xc0 != 0x80).count()
+}
+
+/// The lines drawn around and between a `Table`'s cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Border {
+    /// No lines, just two spaces between columns, like `table!`.
+    None,
+    /// `+`, `-` and `|`.
+    Ascii,
+    /// Thin box-drawing lines.
+    Light,
+    /// Thin lines with rounded corners.
+    Rounded,
+    /// Thick lines.
+    Heavy,
+    /// Double lines.
+    Double,
+}
+
+// The characters for a border: the horizontal and vertical lines, then the left end, the
+// crossing and the right end of the top, middle and bottom rules.
+struct Lines {
+    horizontal: char,
+    vertical:   char,
+    top:        [char; 3],
+    middle:     [char; 3],
+    bottom:     [char; 3],
+}
+
+impl Border {
+    fn lines(self) -> Option<Lines> {
+        let (horizontal, vertical, top, middle, bottom) = match self {
+            Border::None    => return None,
+            Border::Ascii   => ('-', '|', ['+', '+', '+'], ['+', '+', '+'], ['+', '+', '+']),
+            Border::Light   => ('─', '│', ['┌', '┬', '┐'], ['├', '┼', '┤'], ['└', '┴', '┘']),
+            Border::Rounded => ('─', '│', ['╭', '┬', '╮'], ['├', '┼', '┤'], ['╰', '┴', '╯']),
+            Border::Heavy   => ('━', '┃', ['┏', '┳', '┓'], ['┣', '╋', '┫'], ['┗', '┻', '┛']),
+            Border::Double  => ('═', '║', ['╔', '╦', '╗'], ['╠', '╬', '╣'], ['╚', '╩', '╝']),
+        };
+
+        Some(Lines { horizontal, vertical, top, middle, bottom })
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Column {
+    align: Align,
+    wrap:  Option<usize>,
+}
+
+const DEFAULT_COLUMN: Column = Column { align: Align::Left, wrap: None };
+
+/// A table built a row at a time. Each cell is rendered once, when its row is added, so a row can
+/// borrow values that don't outlive the call. Columns are as wide as their widest line, counted
+/// in characters, and a row with fewer cells than the others is filled out with empty ones.
+#[derive(Clone, Default)]
+pub struct Table {
+    header:    Option<Vec<String>>,
+    rows:      Vec<Vec<String>>,
+    columns:   Vec<Column>,
+    border:    Option<Border>,
+    max_width: Option<usize>,
+}
+
+// `max_width` doesn't narrow a column below this, or below its natural width if that's less.
+const MIN_WIDTH: usize = 8;
+
+// The lines of a table's cells, and the width of each column.
+type Cells = Vec<Vec<Vec<String>>>;
+
+fn widths(cells: &Cells, num: usize) -> Vec<usize> {
+    let mut widths = vec![0; num];
+
+    for row in cells {
+        for (col, lines) in row.iter().enumerate() {
+            for line in lines {
+                widths[col] = widths[col].max(chars(line.as_bytes()));
+            }
+        }
+    }
+
+    widths
+}
+
+fn render(cells: &[&dyn ShowDyn]) -> Vec<String> {
+    cells.iter().map(|cell| {
+        let mut buf = Vec::with_capacity(cell.len_dyn());
+
+        __expect_written(cell.write_dyn(&mut buf), "Table::row");
+
+        String::from_utf8_lossy(&buf).into_owned()
+    }).collect()
+}
+
+impl Table {
+    pub fn new() -> Table {
+        Table::default()
+    }
+
+    /// Headers for the columns, set off from the rows by a rule when there's a border.
+    pub fn header(mut self, cells: &[&dyn ShowDyn]) -> Table {
+        self.header = Some(render(cells));
+        self
+    }
+
+    fn column(&mut self, col: usize) -> &mut Column {
+        if col >= self.columns.len() {
+            self.columns.resize(col + 1, DEFAULT_COLUMN);
+        }
+
+        &mut self.columns[col]
+    }
+
+    /// Align the cells of column `col`, counting from zero. Columns are left-aligned by default.
+    pub fn align(mut self, col: usize, align: Align) -> Table {
+        self.column(col).align = align;
+        self
+    }
+
+    /// Wrap the cells of column `col` at word boundaries, as `Wrap` does, so that the column is
+    /// no wider than `width` unless a single word is.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[macro_use] extern crate cats;
+    /// # use cats::table::Table;
+    /// # fn main() {
+    /// let mut table = Table::new().wrap(1, 12);
+    ///
+    /// table.row(&[&"Tom", &"chases mice around the house"]);
+    /// table.row(&[&"Mittens", &"naps"]);
+    ///
+    /// assert_eq!(scat!(table), "\
+    /// Tom      chases mice
+    ///          around the
+    ///          house
+    /// Mittens  naps
+    /// ");
+    /// # }
+    /// ```
+    pub fn wrap(mut self, col: usize, width: usize) -> Table {
+        self.column(col).wrap = Some(width);
+        self
+    }
+
+    /// The lines to draw around the cells. The default is `Border::None`.
+    pub fn border(mut self, border: Border) -> Table {
+        self.border = Some(border);
+        self
+    }
+
+    /// Keep the table within `width` characters, by wrapping its widest columns as far as it
+    /// takes. To fit the console, use `term::width()`. A column isn't narrowed below eight
+    /// characters, so a table with many columns can still be wider.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[macro_use] extern crate cats;
+    /// # use cats::table::{Border, Table};
+    /// # fn main() {
+    /// let mut table = Table::new().border(Border::Ascii).max_width(28);
+    ///
+    /// table.row(&[&"Tom", &"chases mice around the house"]);
+    ///
+    /// assert_eq!(scat!(table), "\
+    /// +-----+--------------------+
+    /// | Tom | chases mice around |
+    /// |     | the house          |
+    /// +-----+--------------------+
+    /// ");
+    /// # }
+    /// ```
+    pub fn max_width(mut self, width: usize) -> Table {
+        self.max_width = Some(width);
+        self
+    }
+
+    /// Add a row of cells.
+    ///
+    /// # Panics
+    /// If a cell fails to write, as a field too narrow for its value does when its `Overflow` is
+    /// `Error`.
+    pub fn row(&mut self, cells: &[&dyn ShowDyn]) -> &mut Table {
+        self.rows.push(render(cells));
+        self
+    }
+
+    // The lines of every cell, after wrapping each column at `wraps`, with the header's first.
+    fn lines(&self, wraps: &[Option<usize>]) -> Cells {
+        self.header.iter().chain(&self.rows).map(|row| {
+            row.iter().enumerate().map(|(col, cell)| {
+                let text = match wraps.get(col).cloned().flatten() {
+                    Some(width) => {
+                        let mut buf = Vec::with_capacity(cell.len());
+
+                        Format::write(&Wrap { width }, cell, &mut buf).ok();
+                        String::from_utf8_lossy(&buf).into_owned()
+                    },
+                    None => cell.clone(),
+                };
+
+                text.split('\n').map(String::from).collect()
+            }).collect()
+        }).collect()
+    }
+
+    fn rule<W: io::Write>(widths: &[usize], lines: &Lines, ends: [char; 3], w: &mut W)
+    -> io::Result<()> {
+        for (col, &width) in widths.iter().enumerate() {
+            let joint = if col == 0 { ends[0] } else { ends[1] };
+
+            Show::write(&joint, w)?;
+            Format::write(&Rep(width + 2), &lines.horizontal, w)?;
+        }
+
+        Show::write(&ends[2], w)?;
+        w.write_all(b"\n")
+    }
+
+    fn lay_out<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut wraps: Vec<_> = self.columns.iter().map(|c| c.wrap).collect();
+
+        let mut cells = self.lines(&wraps);
+        let border    = self.border.unwrap_or(Border::None).lines();
+        let num       = cells.iter().map(Vec::len).max().unwrap_or(0);
+
+        if num == 0 {
+            return Ok(());
+        }
+
+        let mut widths = widths(&cells, num);
+
+        if let Some(max) = self.max_width {
+            let rules      = if border.is_some() { 3 * num + 1 } else { 2 * (num - 1) };
+            let mut fitted = widths.clone();
+
+            // Take a character at a time from the widest column that can spare one.
+            while rules + fitted.iter().sum::<usize>() > max {
+                match (0..num).filter(|&c| fitted[c] > MIN_WIDTH).max_by_key(|&c| fitted[c]) {
+                    Some(c) => fitted[c] -= 1,
+                    None    => break,
+                }
+            }
+
+            if fitted != widths {
+                wraps.resize(num, None);
+
+                for col in (0..num).filter(|&c| fitted[c] < widths[c]) {
+                    wraps[col] = Some(fitted[col]);
+                }
+
+                cells  = self.lines(&wraps);
+                widths = self::widths(&cells, num);
+            }
+        }
+
+        if let Some(ref lines) = border {
+            Table::rule(&widths, lines, lines.top, w)?;
+        }
+
+        for (i, row) in cells.iter().enumerate() {
+            if let Some(lines) = border.as_ref().filter(|_| i == 1 && self.header.is_some()) {
+                Table::rule(&widths, lines, lines.middle, w)?;
+            }
+
+            let height = row.iter().map(Vec::len).max().unwrap_or(1);
+
+            for n in 0..height {
+                let line = |col: usize| row.get(col).and_then(|c| c.get(n)).map_or("", |l| &l[..]);
+
+                // Without a border, a line stops after its last cell with anything in it.
+                let end = match border {
+                    Some(_) => num,
+                    None    => (0..num).rev().find(|&c| !line(c).is_empty()).map_or(0, |c| c + 1),
+                };
+
+                for (col, &width) in widths[..end].iter().enumerate() {
+                    let align = self.columns.get(col).map_or(Align::Left, |c| c.align);
+                    let line  = line(col);
+
+                    let (before, mut after) = Width::new(width).align(align)
+                        .padding(chars(line.as_bytes()));
+
+                    if let Some(ref lines) = border {
+                        Show::write(&lines.vertical, w)?;
+                        Show::write(&' ', w)?;
+                        after += 1;
+                    } else if col + 1 == end {
+                        // Like `table!`, the last cell isn't padded, so lines don't end in spaces.
+                        after = 0;
+                    } else {
+                        after += 2;
+                    }
+
+                    Format::write(&Rep(before), &' ', w)?;
+                    Show::write(&line, w)?;
+                    Format::write(&Rep(after), &' ', w)?;
+                }
+
+                if let Some(ref lines) = border {
+                    Show::write(&lines.vertical, w)?;
+                }
+
+                w.write_all(b"\n")?;
+            }
+        }
+
+        if let Some(ref lines) = border {
+            Table::rule(&widths, lines, lines.bottom, w)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Show for Table {
+    fn len(&self) -> usize {
+        measure(|w| self.lay_out(w))
+    }
+
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| self.lay_out(w))
+    }
+}
+
+// The runtime half of `table!`. The macro renders every cell into one buffer, in order, and records
+// how many bytes each took, which is all that's needed to work out the column widths and lay the
+// table out.
+#[doc(hidden)]
+pub fn __write_table<W: io::Write>(w: &mut W, sep: &str, cells: &[u8], rows: &[Vec<usize>])
+-> io::Result<()> {
+    let mut widths = Vec::new();
+    let mut start  = 0;
+
+    for row in rows {
+        for (col, &len) in row.iter().enumerate() {
+            let width = chars(&cells[start..start + len]);
+
+            if col == widths.len() {
+                widths.push(width);
+            } else if width > widths[col] {
+                widths[col] = width;
+            }
+
+            start += len;
+        }
+    }
+
+    start = 0;
+
+    for row in rows {
+        for (col, &len) in row.iter().enumerate() {
+            let cell = &cells[start..start + len];
+
+            w.write_all(cell)?;
+            start += len;
+
+            // The last cell in a row isn't padded, so lines don't end in spaces.
+            if col + 1 < row.len() {
+                Format::write(&Rep(widths[col] - chars(cell)), &' ', w)?;
+                w.write_all(sep.as_bytes())?;
+            }
+        }
+
+        w.write_all(b"\n")?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::fmt;
+use std::io;
+
+use fmt_writer::FmtWriter;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Something a cat can be written to: either an `io::Write`, or a `fmt::Write` such as a `String`
+/// or a `fmt::Formatter`, adapted with `FmtWriter`. `cat_write!`, `fcat!` and `fcatln!` pick the
+/// right one for their first argument, so all three accept both kinds of writer.
+///
+/// This trait is sealed, and a type that implements both `io::Write` and `fmt::Write` can't be
+/// used directly, since there'd be no telling which was meant.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # fn main() {
+/// let mut s = String::from("lives: ");
+/// let mut v = Vec::new();
+///
+/// fcat!(s, 9, '!').unwrap();
+/// fcat!(v, 9, '!').unwrap();
+///
+/// assert_eq!(s, "lives: 9!");
+/// assert_eq!(v, b"9!");
+/// # }
+/// ```
+pub trait CatTarget: io::Write + sealed::Sealed {
+    /// Called once the cat has been written, to catch anything left unfinished.
+    fn finish(self) -> io::Result<()>;
+}
+
+impl<W: io::Write + ?Sized> sealed::Sealed for &mut W {}
+
+impl<W: io::Write + ?Sized> CatTarget for &mut W {
+    fn finish(self) -> io::Result<()> { Ok(()) }
+}
+
+impl<'a, F: fmt::Write + ?Sized> sealed::Sealed for FmtWriter<'a, F> {}
+
+impl<'a, F: fmt::Write + ?Sized> CatTarget for FmtWriter<'a, F> {
+    fn finish(self) -> io::Result<()> {
+        FmtWriter::finish(self)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "cat wrote invalid utf-8"))
+    }
+}
+
+// The macros call `__cat_target` with both of these traits in scope. Only one of them applies to
+// any given writer, so method resolution picks it, and borrows or reborrows the writer as it goes.
+#[doc(hidden)]
+pub trait __IoTarget: io::Write {
+    fn __cat_target(&mut self) -> &mut Self { self }
+}
+
+impl<W: io::Write + ?Sized> __IoTarget for W {}
+
+#[doc(hidden)]
+pub trait __FmtTarget: fmt::Write {
+    fn __cat_target(&mut self) -> FmtWriter<'_, Self> { FmtWriter::new(self) }
+}
+
+impl<F: fmt::Write + ?Sized> __FmtTarget for F {}
@@ -0,0 +1,287 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::error;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::io;
+
+use error::__expect_written;
+use traits::{Show, ShowDyn, SizeHint};
+
+/// A message with named placeholders, parsed once at runtime and filled in as often as needed.
+/// It's for text that can't be a `scat!` because it isn't known until the program runs, like an
+/// email subject or log line read from a config file.
+///
+/// A placeholder is a name in braces, like `{name}`, made of letters, digits, '_', '.' and '-'.
+/// Write "{{" and "}}" for literal braces. The values come from anything that implements
+/// `Lookup`, such as a `HashMap` or `BTreeMap` from names to `Show` values, or a struct of your
+/// own.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{Template, TemplateError, Lookup, ShowDyn};
+/// # use std::collections::HashMap;
+/// # fn main() {
+/// let subject = Template::parse("Hello {name}, you have {n} messages").unwrap();
+///
+/// let mut values = HashMap::new();
+/// values.insert("name", "Tom");
+/// values.insert("n", "3");
+///
+/// assert_eq!(subject.render(&values).unwrap(), "Hello Tom, you have 3 messages");
+///
+/// struct Inbox { name: String, n: u32 }
+///
+/// impl Lookup for Inbox {
+///     fn lookup(&self, name: &str) -> Option<&dyn ShowDyn> {
+///         match name {
+///             "name" => Some(&self.name),
+///             "n"    => Some(&self.n),
+///             _      => None,
+///         }
+///     }
+/// }
+///
+/// let inbox = Inbox { name: String::from("Mittens"), n: 9 };
+///
+/// assert_eq!(scat!('>', subject.fill(&inbox).unwrap()), ">Hello Mittens, you have 9 messages");
+///
+/// assert_eq!(Template::parse("Hi {name"), Err(TemplateError::Unclosed(3)));
+/// assert_eq!(Template::parse("Hi {}"), Err(TemplateError::BadName(3)));
+/// assert_eq!(subject.render(&HashMap::<&str, u32>::new()),
+///            Err(TemplateError::Missing(String::from("name"))));
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    pieces: Vec<Piece>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Piece {
+    Lit(String),
+    Name(String),
+}
+
+/// What went wrong with a `Template` or the values given to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A '{' at this byte of the template with no '}' to close it.
+    Unclosed(usize),
+
+    /// A '}' at this byte of the template that doesn't close anything.
+    Unopened(usize),
+
+    /// The placeholder starting at this byte of the template has an empty or invalid name.
+    BadName(usize),
+
+    /// There's no value for the placeholder with this name.
+    Missing(String),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TemplateError::Unclosed(at) => write!(f, "unclosed '{{' at byte {} of template", at),
+            TemplateError::Unopened(at) => {
+                write!(f, "unmatched '}}' at byte {} of template, use \"}}}}\" for a literal", at)
+            },
+            TemplateError::BadName(at)  => {
+                write!(f, "invalid placeholder at byte {} of template", at)
+            },
+            TemplateError::Missing(ref name) => write!(f, "no value for placeholder {{{}}}", name),
+        }
+    }
+}
+
+impl error::Error for TemplateError {}
+
+impl From<TemplateError> for io::Error {
+    fn from(e: TemplateError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+}
+
+pub(crate) fn valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || "_.-".contains(c))
+}
+
+impl Template {
+    /// Parse a template, checking that its braces match and its placeholders are well formed.
+    pub fn parse(template: &str) -> Result<Template, TemplateError> {
+        let mut pieces = Vec::new();
+        let mut lit    = String::new();
+        let mut rest   = template.char_indices().peekable();
+
+        while let Some((i, c)) = rest.next() {
+            match c {
+                '{' if rest.peek().map(|&(_, c)| c) == Some('{') => { rest.next(); lit.push('{'); },
+                '}' if rest.peek().map(|&(_, c)| c) == Some('}') => { rest.next(); lit.push('}'); },
+
+                '}' => return Err(TemplateError::Unopened(i)),
+
+                '{' => {
+                    let end = match template[i..].find('}') {
+                        Some(len) => i + len,
+                        None      => return Err(TemplateError::Unclosed(i)),
+                    };
+
+                    let name = &template[i + 1..end];
+
+                    if !valid_name(name) {
+                        return Err(TemplateError::BadName(i));
+                    }
+
+                    if !lit.is_empty() {
+                        pieces.push(Piece::Lit(lit.clone()));
+                        lit.clear();
+                    }
+
+                    pieces.push(Piece::Name(name.into()));
+
+                    while rest.peek().is_some_and(|&(j, _)| j <= end) {
+                        rest.next();
+                    }
+                },
+
+                c => lit.push(c),
+            }
+        }
+
+        if !lit.is_empty() {
+            pieces.push(Piece::Lit(lit));
+        }
+
+        Ok(Template { pieces })
+    }
+
+    /// The names of the placeholders, in the order they appear. A name used twice is listed twice.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.pieces.iter().filter_map(|p| match *p {
+            Piece::Name(ref name) => Some(&name[..]),
+            Piece::Lit(_)         => None,
+        })
+    }
+
+    /// Look up a value for every placeholder, giving something that shows the filled-in template.
+    pub fn fill<'t, 'v, V>(&'t self, values: &'v V) -> Result<Filled<'t, 'v>, TemplateError>
+    where V: Lookup + ?Sized {
+        let values = self.names()
+            .map(|name| values.lookup(name).ok_or_else(|| TemplateError::Missing(name.into())))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Filled { template: self, values })
+    }
+
+    /// Fill in the template and collect it into a `String`.
+    ///
+    /// # Panics
+    /// If a value fails to write, as a field too narrow for it does when its `Overflow` is `Error`.
+    pub fn render<V>(&self, values: &V) -> Result<String, TemplateError>
+    where V: Lookup + ?Sized {
+        let filled  = self.fill(values)?;
+        let mut buf = Vec::with_capacity(filled.size_hint().lower());
+
+        __expect_written(filled.write(&mut buf), "Template::render");
+
+        Ok(String::from_utf8(buf).expect("template generated invalid utf-8"))
+    }
+}
+
+/// A `Template` with a value for each of its placeholders, from `Template::fill`.
+pub struct Filled<'t, 'v> {
+    template: &'t Template,
+    values:   Vec<&'v dyn ShowDyn>,
+}
+
+impl<'t, 'v> Filled<'t, 'v> {
+    // The length of the template's text outside of placeholders.
+    fn lits_len(&self) -> usize {
+        self.template.pieces.iter()
+            .map(|p| match *p { Piece::Lit(ref s) => s.len(), Piece::Name(_) => 0 })
+            .sum()
+    }
+}
+
+impl<'t, 'v> Show for Filled<'t, 'v> {
+    fn len(&self) -> usize {
+        self.values.iter().fold(self.lits_len(), |len, v| len.saturating_add(v.len_dyn()))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        let lits = SizeHint::Exact(self.lits_len());
+
+        self.values.iter().fold(lits, |hint, v| hint + v.size_hint_dyn())
+    }
+
+    fn write<W: io::Write>(&self, mut w: &mut W) -> io::Result<usize> {
+        // There's one value for each placeholder, in order.
+        let mut values  = self.values.iter();
+        let mut written = 0;
+
+        for piece in &self.template.pieces {
+            written += match *piece {
+                Piece::Lit(ref s) => w.write_all(s.as_bytes()).map(|()| s.len())?,
+                Piece::Name(_)    => values.next().map_or(Ok(0), |v| v.write_dyn(&mut w))?,
+            };
+        }
+
+        Ok(written)
+    }
+}
+
+/// Where a `Template` finds the values for its placeholders. Besides maps, it's implemented for
+/// slices and arrays of `(name, value)` pairs, and for `()` when there are no values at all.
+pub trait Lookup {
+    /// The value for the placeholder `name`, if there is one.
+    fn lookup(&self, name: &str) -> Option<&dyn ShowDyn>;
+}
+
+impl Lookup for () {
+    fn lookup(&self, _: &str) -> Option<&dyn ShowDyn> { None }
+}
+
+impl<K, V, S> Lookup for HashMap<K, V, S>
+where K: Borrow<str> + Hash + Eq, V: ShowDyn, S: BuildHasher {
+    fn lookup(&self, name: &str) -> Option<&dyn ShowDyn> {
+        self.get(name).map(|v| v as &dyn ShowDyn)
+    }
+}
+
+impl<V> Lookup for [(&str, V)]
+where V: ShowDyn {
+    fn lookup(&self, name: &str) -> Option<&dyn ShowDyn> {
+        self.iter().find(|&&(n, _)| n == name).map(|(_, v)| v as &dyn ShowDyn)
+    }
+}
+
+impl<V, const N: usize> Lookup for [(&str, V); N]
+where V: ShowDyn {
+    fn lookup(&self, name: &str) -> Option<&dyn ShowDyn> {
+        self[..].lookup(name)
+    }
+}
+
+impl<K, V> Lookup for BTreeMap<K, V>
+where K: Borrow<str> + Ord, V: ShowDyn {
+    fn lookup(&self, name: &str) -> Option<&dyn ShowDyn> {
+        self.get(name).map(|v| v as &dyn ShowDyn)
+    }
+}
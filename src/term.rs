@@ -0,0 +1,118 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! The size of the terminal, for output that should fit it.
+//!
+//! `width` is what most things want: the number of columns in the terminal the program is
+//! running in, or a sensible guess when there isn't one. `Wrap::terminal`, `Table::max_width` and
+//! `ProgressBar` use it to fit their output to the console.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::Wrap;
+//!
+//! # fn main() {
+//! // Under `cargo test` there's no terminal, so this falls back to $COLUMNS or 80 columns.
+//! assert!(cats::term::width() > 0);
+//!
+//! catln!(Wrap::terminal(); "A paragraph that's wrapped to fit the console, however wide it is.");
+//! # }
+//! ```
+
+use std::env;
+
+/// The width if there's no terminal and `COLUMNS` isn't set.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// A terminal's size, in characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Size {
+    pub cols: usize,
+    pub rows: usize,
+}
+
+#[cfg(unix)]
+fn query() -> Option<Size> {
+    use libc::{ioctl, winsize, STDERR_FILENO, STDIN_FILENO, STDOUT_FILENO, TIOCGWINSZ};
+
+    // Whichever of the standard streams is a terminal, since output is often piped while the
+    // program is still run from one.
+    [STDOUT_FILENO, STDERR_FILENO, STDIN_FILENO].iter().find_map(|&fd| {
+        let mut size = winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+
+        // Safe because `size` is a valid `winsize` for the ioctl to fill in.
+        match unsafe { ioctl(fd, TIOCGWINSZ, &mut size) } {
+            0 if size.ws_col > 0 => Some(Size { cols: size.ws_col.into(),
+                                                rows: size.ws_row.into() }),
+            _                    => None,
+        }
+    })
+}
+
+#[cfg(windows)]
+fn query() -> Option<Size> {
+    use std::mem;
+
+    use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+    use windows_sys::Win32::System::Console::{GetConsoleScreenBufferInfo, GetStdHandle,
+                                              CONSOLE_SCREEN_BUFFER_INFO, STD_ERROR_HANDLE,
+                                              STD_INPUT_HANDLE, STD_OUTPUT_HANDLE};
+
+    [STD_OUTPUT_HANDLE, STD_ERROR_HANDLE, STD_INPUT_HANDLE].iter().find_map(|&std| {
+        // Safe because the handle is checked before use, and `info` is plain data for the call to
+        // fill in.
+        unsafe {
+            let handle = GetStdHandle(std);
+
+            if handle.is_null() || handle == INVALID_HANDLE_VALUE {
+                return None;
+            }
+
+            let mut info: CONSOLE_SCREEN_BUFFER_INFO = mem::zeroed();
+
+            if GetConsoleScreenBufferInfo(handle, &mut info) == 0 {
+                return None;
+            }
+
+            // The visible window, not the whole scrollback buffer.
+            let window = info.srWindow;
+            let cols   = (window.Right - window.Left + 1) as usize;
+            let rows   = (window.Bottom - window.Top + 1) as usize;
+
+            if cols > 0 { Some(Size { cols, rows }) } else { None }
+        }
+    })
+}
+
+#[cfg(not(any(unix, windows)))]
+fn query() -> Option<Size> {
+    None
+}
+
+/// The size of the terminal attached to stdout, stderr or stdin, in that order of preference, or
+/// `None` if none of them is a terminal. This asks the terminal every time, so it follows resizes.
+pub fn size() -> Option<Size> {
+    query()
+}
+
+/// How many columns wide the output should be: the terminal's width, or else `COLUMNS` if it's
+/// set to a number, or else `DEFAULT_WIDTH`.
+pub fn width() -> usize {
+    size().map(|s| s.cols)
+        .or_else(|| env::var("COLUMNS").ok().and_then(|c| c.trim().parse().ok()).filter(|&c| c > 0))
+        .unwrap_or(DEFAULT_WIDTH)
+}
@@ -0,0 +1,282 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use std::io;
+
+use spec::{Width, Precision};
+use term;
+use traits::{Show, Format, Rep};
+
+/// Break text at word boundaries so that no line is longer than `width` characters.
+///
+/// Newlines already in the text are kept, other runs of whitespace collapse to a single space, and
+/// a word longer than `width` gets a line to itself rather than being split.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Wrap;
+/// # fn main() {
+/// let s = scat!(Wrap { width: 10 }; "the quick brown fox jumps");
+///
+/// assert_eq!(s, "the quick\nbrown fox\njumps");
+/// # }
+/// ```
+pub struct Wrap {
+    pub width: usize,
+}
+
+impl Wrap {
+    /// Wrap at the width of the terminal, as `term::width` finds it.
+    pub fn terminal() -> Wrap {
+        Wrap { width: term::width() }
+    }
+
+    // Both `len` and `write` walk the same sequence of pieces, so they can't disagree.
+    fn layout<F>(&self, s: &str, mut emit: F) -> io::Result<()>
+    where F: FnMut(&str) -> io::Result<()> {
+        for (i, line) in s.split('\n').enumerate() {
+            if i > 0 {
+                emit("\n")?;
+            }
+
+            let mut col = 0;
+
+            for word in line.split_whitespace() {
+                let word_len = word.chars().count();
+
+                if col > 0 {
+                    if col + 1 + word_len <= self.width {
+                        emit(" ")?;
+                        col += 1;
+                    } else {
+                        emit("\n")?;
+                        col = 0;
+                    }
+                }
+
+                emit(word)?;
+                col += word_len;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Format<T> for Wrap
+where T: AsRef<str> {
+    fn len(&self, t: &T) -> usize {
+        let mut len = 0;
+
+        self.layout(t.as_ref(), |piece| { len += piece.len(); Ok(()) }).ok();
+
+        len
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let mut written = 0;
+
+        self.layout(t.as_ref(), |piece| {
+            written += Show::write(piece, w)?;
+            Ok(())
+        })?;
+
+        Ok(written)
+    }
+}
+
+/// Replace each tab with enough spaces to reach the next multiple of `width` columns.
+///
+/// Columns are counted in characters and start over after every newline. A `width` of zero
+/// removes tabs entirely.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::ExpandTabs;
+/// # fn main() {
+/// let s = scat!(ExpandTabs { width: 4 }; "a\tbc\td\n\te");
+///
+/// assert_eq!(s, "a   bc  d\n    e");
+/// # }
+/// ```
+pub struct ExpandTabs {
+    pub width: usize,
+}
+
+impl ExpandTabs {
+    fn layout<F>(&self, s: &str, mut emit: F) -> io::Result<()>
+    where F: FnMut(&str, usize) -> io::Result<()> {
+        let mut col = 0;
+
+        for (i, run) in s.split('\t').enumerate() {
+            if i > 0 && self.width > 0 {
+                let spaces = self.width - col % self.width;
+
+                emit(" ", spaces)?;
+                col += spaces;
+            }
+
+            emit(run, 1)?;
+
+            col = match run.rfind('\n') {
+                Some(nl) => run[nl + 1..].chars().count(),
+                None     => col + run.chars().count(),
+            };
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Format<T> for ExpandTabs
+where T: AsRef<str> {
+    fn len(&self, t: &T) -> usize {
+        let mut len = 0;
+
+        self.layout(t.as_ref(), |piece, n| { len += n * piece.len(); Ok(()) }).ok();
+
+        len
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let mut written = 0;
+
+        self.layout(t.as_ref(), |piece, n| {
+            for _ in 0..n {
+                written += Show::write(piece, w)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(written)
+    }
+}
+
+/// Replace every character except the last `show_last` with `mask`, for logging secrets.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Masked;
+/// # fn main() {
+/// let s = scat!(Masked { show_last: 4, mask: '*' }; "4111111111111111");
+///
+/// assert_eq!(s, "************1111");
+/// # }
+/// ```
+pub struct Masked {
+    pub show_last: usize,
+    pub mask:      char,
+}
+
+impl Masked {
+    // Split `s` into the number of characters to mask and the tail which is shown as-is.
+    fn split<'a>(&self, s: &'a str) -> (usize, &'a str) {
+        let hidden = s.chars().count().saturating_sub(self.show_last);
+
+        match s.char_indices().nth(hidden) {
+            Some((idx, _)) => (hidden, &s[idx..]),
+            None           => (hidden, ""),
+        }
+    }
+}
+
+impl<T> Format<T> for Masked
+where T: AsRef<str> {
+    fn len(&self, t: &T) -> usize {
+        let (hidden, shown) = self.split(t.as_ref());
+
+        Format::len(&Rep(hidden), &self.mask) + shown.len()
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let (hidden, shown) = self.split(t.as_ref());
+
+        Ok(Format::write(&Rep(hidden), &self.mask, w)? + Show::write(shown, w)?)
+    }
+}
+
+/// Surround a value with the `quote` character on both sides. Nothing inside is escaped.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::Quote;
+/// # fn main() {
+/// let s = scat!("name=", Quote('\''); "Tom");
+///
+/// assert_eq!(s, "name='Tom'");
+/// # }
+/// ```
+pub struct Quote(pub char);
+
+impl<T> Format<T> for Quote
+where T: Show {
+    fn len(&self, t: &T) -> usize { 2 * self.0.len_utf8() + Show::len(t) }
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        Ok(Show::write(&self.0, w)? + Show::write(t, w)? + Show::write(&self.0, w)?)
+    }
+}
+
+/// Fit a string into a column, like `%-20.10s` in C: cut it down to the `precision` in characters,
+/// if there is one, then pad it out to the `width`. One that's still wider than the width is
+/// handled according to the width's `overflow`.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{FormattedStr, Width, Precision};
+/// # fn main() {
+/// let col = FormattedStr { width: Width::new(6).fill('.'), precision: Some(Precision(4)) };
+///
+/// assert_eq!(scat!('|', col; "Mittens", '|'), "|Mitt..|");
+/// assert_eq!(scat!('|', col; "Tom", '|'), "|Tom...|");
+/// # }
+/// ```
+pub struct FormattedStr {
+    pub width:     Width,
+    pub precision: Option<Precision>,
+}
+
+impl FormattedStr {
+    // The part of `s` that's shown, and how many characters wide it is.
+    fn visible<'a>(&self, s: &'a str) -> (&'a str, usize) {
+        let s = match self.precision {
+            Some(p) => p.truncate(s),
+            None    => s,
+        };
+
+        (s, s.chars().count())
+    }
+}
+
+impl<T> Format<T> for FormattedStr
+where T: AsRef<str> {
+    fn len(&self, t: &T) -> usize {
+        let (s, chars) = self.visible(t.as_ref());
+
+        self.width.len(&s, chars)
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let (s, chars) = self.visible(t.as_ref());
+
+        self.width.write(&s, chars, w)
+    }
+}
@@ -16,10 +16,15 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
 use std::cmp;
-use std::io;
-use std::num::Wrapping;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops;
+
+use error::__expect_written;
+use spec::{FieldOverflow, Overflow, Width};
 
 /// A trait for types that know how to display themselves.
+#[allow(clippy::len_without_is_empty)]
 pub trait Show {
     /// How many bytes will the utf8-encoded string representation of `self` take?
     fn len(&self) -> usize;
@@ -27,6 +32,82 @@ pub trait Show {
     /// Write the string resentation of `self` to `w`. The number of bytes written must be exactly
     /// the same as the number returned by `self.len()`.
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize>;
+
+    /// How many bytes `write` is expected to take, for sizing buffers. The default is exactly
+    /// `self.len()`. Impls that can only find their length by doing the work twice, like `Disp`,
+    /// return a lower bound instead, and buffers built from them grow as needed.
+    fn size_hint(&self) -> SizeHint { SizeHint::Exact(self.len()) }
+
+    /// The bytes `write` would put out, if `self` already holds them in one piece, as a string
+    /// does. `fcat_vectored!` passes these to the writer where they are instead of copying them.
+    /// The default is `None`.
+    fn stored_bytes(&self) -> Option<&[u8]> { None }
+
+    /// Render `self` into a new `String` of exactly `self.len()` bytes.
+    ///
+    /// # Panics
+    /// If `write` fails, as a field too narrow for its value does when its `Overflow` is `Error`,
+    /// or produces invalid utf-8.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cats::Show;
+    /// let s = (-42i32).to_cat_string();
+    ///
+    /// assert_eq!(s, "-42");
+    /// assert_eq!(s.capacity(), 3);
+    /// ```
+    fn to_cat_string(&self) -> String {
+        let v = __expect_written(render(self), "Show::to_cat_string");
+
+        String::from_utf8(v).expect("Show::to_cat_string generated invalid utf-8")
+    }
+
+    /// Render `self` into a new `Vec<u8>` of exactly `self.len()` bytes.
+    ///
+    /// # Panics
+    /// If `write` fails, as a field too narrow for its value does when its `Overflow` is `Error`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cats::Show;
+    /// assert_eq!("meow".write_to_vec(), b"meow");
+    /// ```
+    fn write_to_vec(&self) -> Vec<u8> {
+        __expect_written(render(self), "Show::write_to_vec")
+    }
+
+    /// Write `self` to `w` through a buffer sized by `self.len()`, so that `w` sees a single
+    /// `write_all`. Returns the number of bytes written. If `self` fails to write, nothing reaches
+    /// `w`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use cats::{Show, Formatted, Pad, Align, Overflow};
+    /// let mut out = Vec::new();
+    ///
+    /// assert_eq!(1234u32.write_to(&mut out).unwrap(), 4);
+    /// assert_eq!(out, b"1234");
+    ///
+    /// let narrow = Pad { width: 2, fill: ' ', align: Align::Left, overflow: Overflow::Error };
+    ///
+    /// assert!(Formatted(narrow, &"Tom").write_to(&mut out).is_err());
+    /// assert_eq!(out, b"1234");
+    /// ```
+    fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        let v = render(self)?;
+
+        w.write_all(&v).map(|()| v.len())
+    }
+}
+
+// Render `t` into a new `Vec<u8>` of exactly `t.len()` bytes. Only `t` itself can make this fail.
+fn render<T: Show + ?Sized>(t: &T) -> io::Result<Vec<u8>> {
+    let mut v = Vec::with_capacity(t.len());
+
+    t.write(&mut v)?;
+
+    Ok(v)
 }
 
 /// A trait for types that know how to format another type.
@@ -37,21 +118,209 @@ pub trait Format<T> {
     /// Write the string resentation of `t` formatted by `self` to `w`. The number of bytes
     /// written must be exactly the same as the number returned by `self.len(t)`.
     fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize>;
+
+    /// Like `Show::size_hint`, for `t` formatted by `self`.
+    fn size_hint(&self, t: &T) -> SizeHint { SizeHint::Exact(self.len(t)) }
+}
+
+/// How many bytes a `Show` or `Format` expects to write: either exactly this many, or at least
+/// this many when finding out for sure would take a second pass. Hints add up, staying exact only
+/// while every part is exact, and saturate at `usize::MAX` rather than overflowing.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{SizeHint, Disp};
+/// # fn main() {
+/// assert_eq!(cat_size_hint!("Meow", 42), SizeHint::Exact(6));
+/// assert_eq!(cat_size_hint!("Meow", Disp(42)), SizeHint::AtLeast(4));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SizeHint {
+    Exact(usize),
+    AtLeast(usize),
 }
 
+impl SizeHint {
+    /// The fewest bytes that will be written.
+    pub fn lower(&self) -> usize {
+        match *self {
+            SizeHint::Exact(n) | SizeHint::AtLeast(n) => n,
+        }
+    }
+}
+
+impl ops::Add for SizeHint {
+    type Output = SizeHint;
+
+    fn add(self, other: SizeHint) -> SizeHint {
+        let lower = self.lower().saturating_add(other.lower());
+
+        match (self, other) {
+            (SizeHint::Exact(_), SizeHint::Exact(_)) => SizeHint::Exact(lower),
+            _                                        => SizeHint::AtLeast(lower),
+        }
+    }
+}
+
+/// A thin wrapper over a writer for `Show` impls, which writes chars and strings as utf-8 and
+/// returns how many bytes each write took, ready to be added up.
+///
+/// # Examples
+/// ```
+/// # use cats::{Show, Utf8Write};
+/// # use std::io;
+/// struct Stars(usize);
+///
+/// impl Show for Stars {
+///     fn len(&self) -> usize { 2 + self.0 * '★'.len_utf8() }
+///
+///     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+///         let mut w = Utf8Write(w);
+///
+///         Ok(w.push('[')? + w.push_n('★', self.0)? + w.push(']')?)
+///     }
+/// }
+///
+/// assert_eq!(Stars(3).to_cat_string(), "[★★★]");
+/// ```
 pub struct Utf8Write<'x, W: io::Write + 'x>(pub &'x mut W);
 
 impl<'x, W: io::Write + 'x> Utf8Write<'x, W> {
-    fn push(&mut self, c: char) -> io::Result<usize> {
+    /// Write a single char.
+    pub fn push(&mut self, c: char) -> io::Result<usize> {
         let mut buf = [0u8; 4];
-        let limit = c.encode_utf8(&mut buf).unwrap();
+        let limit = c.encode_utf8(&mut buf).len();
 
         self.0.write_all(&buf[0..limit]).map(|()| limit)
     }
 
-    fn push_str(&mut self, s: &str) -> io::Result<usize> {
+    /// Write `n` copies of a char.
+    pub fn push_n(&mut self, c: char, n: usize) -> io::Result<usize> {
+        let mut buf = [0u8; 4];
+        let bytes = c.encode_utf8(&mut buf).as_bytes();
+
+        for _ in 0..n {
+            self.0.write_all(bytes)?;
+        }
+
+        Ok(n * bytes.len())
+    }
+
+    /// Write a string.
+    pub fn push_str(&mut self, s: &str) -> io::Result<usize> {
         self.0.write_all(s.as_bytes()).map(|()| s.len())
     }
+
+    /// Write the output of `format_args!`.
+    pub fn push_fmt(&mut self, args: fmt::Arguments) -> io::Result<usize> {
+        let mut counter = CountingWriter::new(&mut *self.0);
+
+        counter.write_fmt(args)?;
+
+        Ok(counter.count)
+    }
+}
+
+/// Forwards to an inner writer while keeping a tally of the bytes that went through. Wrapping
+/// `io::sink()` gives a writer that only counts, which is an easy way to implement `Show::len` for
+/// a type whose output is hard to measure ahead of time.
+///
+/// # Examples
+/// ```
+/// # use cats::{CountingWriter, Show};
+/// # use std::io::{self, Write};
+/// struct Temperature(f64);
+///
+/// impl Show for Temperature {
+///     fn len(&self) -> usize {
+///         let mut counter = CountingWriter::new(io::sink());
+///
+///         self.write(&mut counter).ok();
+///
+///         counter.count
+///     }
+///
+///     fn write<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+///         let s = format!("{:.1}°C", self.0);
+///
+///         w.write_all(s.as_bytes()).map(|()| s.len())
+///     }
+/// }
+///
+/// assert_eq!(Temperature(21.06).len(), "21.1°C".len());
+/// ```
+pub struct CountingWriter<W: io::Write> {
+    pub inner: W,
+    pub count: usize,
+}
+
+impl<W: io::Write> CountingWriter<W> {
+    pub fn new(inner: W) -> CountingWriter<W> {
+        CountingWriter { inner, count: 0 }
+    }
+}
+
+impl<W: io::Write> io::Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.count += n;
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+// How many bytes `write` puts out, found by having it write them to a `CountingWriter` that only
+// counts. This is how `len` goes for anything whose output is hard to measure ahead of time, and
+// `write_counted` is how its `write` goes.
+pub(crate) fn measure<F, R>(write: F) -> usize
+where F: FnOnce(&mut CountingWriter<io::Sink>) -> io::Result<R> {
+    let mut counter = CountingWriter::new(io::sink());
+
+    write(&mut counter).ok();
+
+    counter.count
+}
+
+// Have `write` write to `w`, and return how many bytes it wrote.
+pub(crate) fn write_counted<W, F, R>(w: W, write: F) -> io::Result<usize>
+where W: io::Write, F: FnOnce(&mut CountingWriter<W>) -> io::Result<R> {
+    let mut counter = CountingWriter::new(w);
+
+    write(&mut counter)?;
+
+    Ok(counter.count)
+}
+
+/// Where a `FormattedInt` puts its padding, relative to the sign and prefix.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{FormattedInt, PadPosition, SignPolicy};
+/// # fn main() {
+/// const ZEROS:  FormattedInt<'static> = FormattedInt::decimal().sign(SignPolicy::Plus).min_len(5);
+/// const SPACES: FormattedInt<'static> = ZEROS.pad(' ').pad_at(PadPosition::BeforeSign);
+///
+/// assert_eq!(scat!(ZEROS; 42), "+00042");
+/// assert_eq!(scat!(SPACES; 42), "   +42");
+/// assert_eq!(scat!(SPACES; -42), "   -42");
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PadPosition {
+    /// Pad before everything else, as in "   +0x2a".
+    BeforeSign,
+
+    /// Pad between the sign and the prefix, as in "+   0x2a".
+    AfterSign,
+
+    /// Pad between the prefix and the digits, as in "+0x0002a". This is the default, and the only
+    /// position where zero padding is grouped with the digits.
+    AfterPrefix,
 }
 
 /// What should be printed before a positive integer?
@@ -64,272 +333,630 @@ pub enum SignPolicy {
 
     /// Don't print anything before positive numbers, as in "372"
     Empty,
+
+    /// Don't print anything before positive numbers, and wrap negative numbers in parentheses
+    /// instead of using a '-' sign, as in "(372)"
+    Parens,
 }
 
+/// How to show an integer: the digits to use, which sets the base, along with padding, sign,
+/// grouping and any prefix or suffix. It formats every integer type up to 128 bits.
+///
+/// Negative numbers are shown as the sign followed by their magnitude, which is taken in the
+/// unsigned type of the same width, so that `MIN` works in every base.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{FormattedInt, SignPolicy, HEX};
+/// # fn main() {
+/// let parens = FormattedInt::decimal().sign(SignPolicy::Parens);
+///
+/// assert_eq!(scat!(i64::MIN), "-9223372036854775808");
+/// assert_eq!(scat!(HEX; i64::MIN), "-8000000000000000");
+/// assert_eq!(scat!(parens; i64::MIN), "(9223372036854775808)");
+/// assert_eq!(cat_len!(HEX; i64::MIN), 17);
+/// assert_eq!(scat!(u64::MAX), "18446744073709551615");
+/// assert_eq!(scat!(HEX; -255i32), "-ff");
+/// assert_eq!(scat!(u128::MAX), "340282366920938463463374607431768211455");
+/// assert_eq!(scat!(HEX; i128::MIN), "-80000000000000000000000000000000");
+/// # }
+/// ```
 pub struct FormattedInt<'x> {
     pub prefix:  &'x str,
     pub suffix:  &'x str,
     pub digits:  &'x [char],
     pub min_len: usize,
+    pub pad:     char,
     pub sign:    SignPolicy,
+
+    pub group_sep:  Option<char>,
+    pub group_size: usize,
+
+    /// Show ASCII letter digits in uppercase, so the same digit table serves both cases.
+    pub uppercase: bool,
+
+    pub pad_at: PadPosition,
+
+    /// Make `min_len` the width of the whole integer, sign, prefix and suffix included, instead of
+    /// just its digits.
+    pub full_width: bool,
+
+    /// What to do with an integer that needs more than `min_len`.
+    pub overflow: Overflow,
 }
 
+/// Building a `FormattedInt` one setting at a time. Every method is a `const fn`, so presets like
+/// `HEX` can be defined as constants.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::FormattedInt;
+/// # fn main() {
+/// const ADDR: FormattedInt<'static> = FormattedInt::hex().min_len(8).prefix("0x");
+///
+/// assert_eq!(scat!(ADDR; 0xbeefu32), "0x0000beef");
+/// # }
+/// ```
 impl<'x> FormattedInt<'x> {
-    fn sign_len(&self) -> usize {
-        match self.sign {
-            SignPolicy::Plus | SignPolicy::Space => 1,
-            SignPolicy::Empty                    => 0,
-        }
+    /// Plain base-10, the same as showing the integer directly.
+    pub const fn decimal() -> FormattedInt<'static> {
+        FormattedInt::with_digits(DECIMAL_DIGITS)
     }
 
-    fn with_fanciness(&self, s: usize) -> usize {
-        let padded = if s < self.min_len { self.min_len } else { s };
-
-        padded + self.prefix.len() + self.suffix.len() + self.sign_len()
+    /// Lowercase base-16 without a prefix, padded with zeros.
+    pub const fn hex() -> FormattedInt<'static> {
+        FormattedInt::with_digits(HEX_DIGITS)
     }
 
-    fn num_digits(&self, x: u64) -> usize {
-        let mut length = 1;
-        let base       = Wrapping(self.digits.len() as u64);
-        let mut limit  = base;
+    /// Base-N, where N is the number of `digits`, padded with the first digit.
+    pub const fn with_digits(digits: &'x [char]) -> FormattedInt<'x> {
+        FormattedInt {
+            prefix:  "",
+            suffix:  "",
+            digits,
+            min_len: 0,
+            pad:     digits[0],
+            sign:    SignPolicy::Empty,
+
+            group_sep:  None,
+            group_size: 0,
 
-        while base * limit > limit {
-            if limit > Wrapping(x) { return length; }
+            uppercase: false,
 
-            length = length + 1;
-            limit  = limit  * base;
+            pad_at:     PadPosition::AfterPrefix,
+            full_width: false,
+            overflow:   Overflow::Expand,
         }
+    }
 
-        length
+    pub const fn prefix(self, prefix: &'x str) -> FormattedInt<'x> {
+        FormattedInt { prefix, .. self }
     }
 
-    fn reverse(&self, mut x: u64) -> u64 {
-        let mut r = 0;
-        let base  = self.digits.len() as u64;
+    pub const fn suffix(self, suffix: &'x str) -> FormattedInt<'x> {
+        FormattedInt { suffix, .. self }
+    }
 
-        while x != 0 {
-            r = (r * base) + (x % base);
-            x = x / base;
-        }
+    pub const fn min_len(self, min_len: usize) -> FormattedInt<'x> {
+        FormattedInt { min_len, .. self }
+    }
 
-        r
+    pub const fn pad(self, pad: char) -> FormattedInt<'x> {
+        FormattedInt { pad, .. self }
     }
-}
 
-impl<'x> Format<u64> for FormattedInt<'x> {
-    // TODO: This assumes that all digits require 1 byte to encode.
-    fn len(&self, x: &u64) -> usize {
-        self.with_fanciness(self.num_digits(*x))
+    pub const fn sign(self, sign: SignPolicy) -> FormattedInt<'x> {
+        FormattedInt { sign, .. self }
     }
 
-    fn write<W: io::Write>(&self, x: &u64, w: &mut W) -> io::Result<usize> {
-        let mut written = 0;
+    /// Put `sep` between every `size` digits, counting from the right.
+    pub const fn group(self, sep: char, size: usize) -> FormattedInt<'x> {
+        FormattedInt { group_sep: Some(sep), group_size: size, .. self }
+    }
 
-        let base = self.digits.len() as u64;
+    /// Pad out to the width's minimum with its fill character. Integers are always right-aligned,
+    /// so the width's alignment is ignored.
+    pub const fn width(self, width: Width) -> FormattedInt<'x> {
+        FormattedInt { min_len: width.min, pad: width.fill, overflow: width.overflow, .. self }
+    }
 
-        let mut utf8_w = Utf8Write(w);
+    pub const fn pad_at(self, pad_at: PadPosition) -> FormattedInt<'x> {
+        FormattedInt { pad_at, .. self }
+    }
+
+    /// Count the sign, prefix and suffix toward `min_len`, so that it's the width of the whole
+    /// thing, like the width in `%08d`.
+    ///
+    /// # Examples
+    /// ```
+    /// # #[macro_use] extern crate cats;
+    /// # use cats::FormattedInt;
+    /// # fn main() {
+    /// const ADDR: FormattedInt<'static> = FormattedInt::hex().prefix("0x").min_len(6);
+    ///
+    /// assert_eq!(scat!(ADDR; 0xffu8), "0x0000ff");
+    /// assert_eq!(scat!(ADDR.full_width(); 0xffu8), "0x00ff");
+    /// assert_eq!(scat!(FormattedInt::decimal().min_len(5).full_width(); -42), "-0042");
+    /// # }
+    /// ```
+    pub const fn full_width(self) -> FormattedInt<'x> {
+        FormattedInt { full_width: true, .. self }
+    }
 
-        // Pad with the zero digit until the minimum width is reached.
-        let padding = self.min_len - cmp::min(self.num_digits(*x), self.min_len);
+    /// Choose what happens to an integer that needs more than `min_len`. Truncating keeps the
+    /// low-order digits, and never drops the sign, prefix or suffix.
+    pub const fn overflow(self, overflow: Overflow) -> FormattedInt<'x> {
+        FormattedInt { overflow, .. self }
+    }
 
-        written += match self.sign {
-            SignPolicy::Plus  => try!(utf8_w.push('+')),
-            SignPolicy::Space => try!(utf8_w.push(' ')),
-            SignPolicy::Empty => 0,
-        };
+    /// Show letter digits in uppercase.
+    pub const fn upper(self) -> FormattedInt<'x> {
+        FormattedInt { uppercase: true, .. self }
+    }
 
-        written += try!(utf8_w.push_str(self.prefix));
+    fn digit(&self, i: usize) -> char {
+        if self.uppercase { self.digits[i].to_ascii_uppercase() } else { self.digits[i] }
+    }
 
-        for _ in 0..padding {
-            written += try!(utf8_w.push(self.digits[0]));
+    // The two-digit lookup table for plain decimal or hex, which is how nearly every integer is
+    // shown. Grouped digits go one at a time instead.
+    fn pairs(&self) -> Option<&'static [u8]> {
+        if self.group_sep.is_some() && self.group_size > 0 {
+            None
+        } else if self.digits == DECIMAL_DIGITS {
+            Some(&DECIMAL_PAIRS)
+        } else if self.digits == HEX_DIGITS {
+            Some(&HEX_PAIRS)
+        } else {
+            None
         }
+    }
 
-        let mut r = self.reverse(*x);
+    // How many characters of an integer with `num_digits` digits, and `sign` and `close` around
+    // them, count toward `min_len`.
+    fn used(&self, num_digits: usize, sign: &str, close: &str) -> usize {
+        if self.full_width {
+            let affixes: usize = [sign, close, self.prefix, self.suffix].iter()
+                .map(|s| s.chars().count())
+                .sum();
 
-        if r == 0 {
-            written += try!(utf8_w.push(self.digits[0]));
+            num_digits + self.separators(num_digits, 0) + affixes
         } else {
-            for _ in 0..self.num_digits(*x) {
-                written += try!(utf8_w.push(self.digits[(r % base) as usize]));
-                r /= base;
-            }
+            num_digits
         }
+    }
 
-        Ok(written + try!(utf8_w.push_str(self.suffix)))
+    // How many pad characters go in front of `num_digits` digits with `sign` and `close` around
+    // them.
+    fn padding(&self, num_digits: usize, sign: &str, close: &str) -> usize {
+        self.min_len - cmp::min(self.used(num_digits, sign, close), self.min_len)
     }
-}
 
-impl<'x> Format<u32> for FormattedInt<'x> {
-    fn len(&self, x: &u32) -> usize { Format::len(self, &(*x as u64)) }
-    fn write<W: io::Write>(&self, x: &u32, w: &mut W) -> io::Result<usize> {
-        Format::write(self, &(*x as u64), w)
+    // How many of an integer's `num_digits` digits are shown. Only `Overflow::Truncate` leaves any
+    // out, dropping the most significant until the rest fit, but always keeping one.
+    fn fit(&self, num_digits: usize, sign: &str, close: &str) -> io::Result<usize> {
+        let used = self.used(num_digits, sign, close);
+
+        if used <= self.min_len {
+            return Ok(num_digits);
+        }
+
+        match self.overflow {
+            Overflow::Expand   => Ok(num_digits),
+            Overflow::Truncate => {
+                Ok((1..num_digits).rev()
+                    .find(|&n| self.used(n, sign, close) <= self.min_len)
+                    .unwrap_or(1))
+            },
+            Overflow::Error    => Err(FieldOverflow { width: self.min_len, chars: used }.into()),
+        }
     }
-}
 
-impl<'x> Format<u16> for FormattedInt<'x> {
-    fn len(&self, x: &u16) -> usize { Format::len(self, &(*x as u64)) }
-    fn write<W: io::Write>(&self, x: &u16, w: &mut W) -> io::Result<usize> {
-        Format::write(self, &(*x as u64), w)
+    // The length of an integer with `num_digits` digits, when they're all one byte each.
+    fn ascii_len(&self, num_digits: usize, sign: &str, close: &str) -> usize {
+        let padding = self.padding(num_digits, sign, close);
+
+        num_digits + padding * self.pad.len_utf8() + sign.len() + close.len() + self.prefix.len()
+          + self.suffix.len() + self.separators_len(num_digits, padding)
     }
-}
 
-impl<'x> Format<u8> for FormattedInt<'x> {
-    fn len(&self, x: &u8) -> usize { Format::len(self, &(*x as u64)) }
-    fn write<W: io::Write>(&self, x: &u8, w: &mut W) -> io::Result<usize> {
-        Format::write(self, &(*x as u64), w)
+    // Padding with the zero digit right before the digits is grouped along with them, so that
+    // zero-padded hex comes out as "0000_beef". Any other padding is left out of the groups.
+    fn grouped_len(&self, num_digits: usize, padding: usize) -> usize {
+        if self.grouped_padding() { num_digits + padding } else { num_digits }
     }
-}
 
-impl<'x> Format<usize> for FormattedInt<'x> {
-    fn len(&self, x: &usize) -> usize { Format::len(self, &(*x as u64)) }
-    fn write<W: io::Write>(&self, x: &usize, w: &mut W) -> io::Result<usize> {
-        Format::write(self, &(*x as u64), w)
+    fn grouped_padding(&self) -> bool {
+        self.pad == self.digits[0] && self.pad_at == PadPosition::AfterPrefix
     }
-}
 
-impl<'x> Format<i64> for FormattedInt<'x> {
-    fn len(&self, x: &i64) -> usize {
+    fn sign_str(&self) -> &'static str {
         match self.sign {
-            SignPolicy::Empty if *x < 0 => Format::len(self, &(x.abs() as u64)) + 1,
-            _                           => Format::len(self, &(x.abs() as u64)),
+            SignPolicy::Plus                       => "+",
+            SignPolicy::Space                      => " ",
+            SignPolicy::Empty | SignPolicy::Parens => "",
         }
     }
 
-    fn write<W: io::Write>(&self, x: &i64, w: &mut W) -> io::Result<usize> {
-        if *x < 0 {
-            Ok(try!(Utf8Write(w).push('-')) +
-                try!(Format::write(&FormattedInt {
-                    sign: SignPolicy::Empty,
-                    .. *self
-                }, &(x.abs() as u64), w)))
-        } else {
-            Format::write(self, &(*x as u64), w)
-        }
+    fn push_padding<W: io::Write>(&self, w: &mut Utf8Write<W>, at: PadPosition, padding: usize)
+        -> io::Result<usize> {
+        if self.pad_at == at { w.push_n(self.pad, padding) } else { Ok(0) }
     }
-}
 
-const DECIMAL_DIGITS: &'static [char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+    // How many group separators go among the digits and any grouped padding.
+    fn separators(&self, num_digits: usize, padding: usize) -> usize {
+        match self.group_sep {
+            Some(_) if self.group_size > 0 => {
+                (self.grouped_len(num_digits, padding) - 1) / self.group_size
+            },
+            _ => 0,
+        }
+    }
 
-const HEX_DIGITS: &'static [char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
-                                       'a', 'b', 'c', 'd', 'e', 'f'];
+    fn separators_len(&self, num_digits: usize, padding: usize) -> usize {
+        self.separators(num_digits, padding) * self.group_sep.map_or(0, char::len_utf8)
+    }
 
-pub const HEX: FormattedInt<'static> = FormattedInt {
-    prefix:  "",
-    suffix:  "",
-    digits:  HEX_DIGITS,
-    min_len: 0,
-    sign:    SignPolicy::Empty,
-};
+    // Write a group separator if `left` grouped digits remain and they start a new group.
+    fn push_separator<W: io::Write>(&self, w: &mut Utf8Write<W>, left: usize) -> io::Result<usize> {
+        let starts_group = self.group_size > 0 && left > 0 && left % self.group_size == 0;
 
-impl Show for u64 {
-    fn len(&self) -> usize {
-        Format::len(&FormattedInt {
-            prefix:  "",
-            suffix:  "",
-            digits:  DECIMAL_DIGITS,
-            min_len: 0,
-            sign:    SignPolicy::Empty,
-        }, self)
+        match self.group_sep {
+            Some(sep) if starts_group => w.push(sep),
+            _                         => Ok(0),
+        }
     }
+}
 
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Format::write(&FormattedInt {
-            prefix:  "",
-            suffix:  "",
-            digits:  DECIMAL_DIGITS,
-            min_len: 0,
-            sign:    SignPolicy::Empty,
-        }, self, w)
-    }
+// How many digits an integer has in a given base.
+trait NumDigits: Sized {
+    fn num_digits(self, base: Self) -> usize;
 }
 
-impl Show for u32 {
-    fn len(&self) -> usize { Show::len(&(*self as u64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as u64), w)
-    }
+// Writing the magnitude of an integer, which is where all of `FormattedInt` but the choice of sign
+// happens. `sign` is written wherever the sign goes, and `close` after everything else, which is
+// how negative numbers get their parentheses.
+trait WriteMagnitude: Sized {
+    fn magnitude_len(self, f: &FormattedInt, sign: &str, close: &str) -> usize;
+
+    fn write_magnitude<W: io::Write>(self, f: &FormattedInt, sign: &str, close: &str, w: &mut W)
+        -> io::Result<usize>;
 }
 
-impl Show for u16 {
-    fn len(&self) -> usize { Show::len(&(*self as u64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as u64), w)
+// Powers of ten up to the largest that fits in a `u128`.
+const POW10: [u128; 39] = {
+    let mut pow10 = [1; 39];
+    let mut i     = 1;
+
+    while i < pow10.len() {
+        pow10[i] = pow10[i - 1] * 10;
+        i += 1;
     }
+
+    pow10
+};
+
+// Integers are formatted by `u64` and `u128`, with signed types shown as a sign followed by their
+// magnitude in the matching unsigned type. Narrower types are widened first.
+macro_rules! impl_int {
+    (@unsigned $($t:ty),*) => ($(
+        impl NumDigits for $t {
+            fn num_digits(self, base: $t) -> usize {
+                // Zero has one digit, the same as one, and setting the low bit can't add a digit in
+                // base 10 or a power of two.
+                let x    = self | 1;
+                let bits = <$t>::BITS - x.leading_zeros();
+
+                if base.is_power_of_two() && base > 1 {
+                    ((bits + base.trailing_zeros() - 1) / base.trailing_zeros()) as usize
+                } else if base == 10 {
+                    // floor(bits * log10(2)), which is either the number of digits or one short.
+                    let guess = (bits * 1233 >> 12) as usize;
+
+                    guess + (x as u128 >= POW10[guess]) as usize
+                } else {
+                    let mut x      = self;
+                    let mut length = 1;
+
+                    while x >= base {
+                        x      /= base;
+                        length += 1;
+                    }
+
+                    length
+                }
+            }
+        }
+
+        impl<'x> Format<$t> for FormattedInt<'x> {
+            fn len(&self, x: &$t) -> usize {
+                x.magnitude_len(self, self.sign_str(), "")
+            }
+
+            fn write<W: io::Write>(&self, x: &$t, w: &mut W) -> io::Result<usize> {
+                x.write_magnitude(self, self.sign_str(), "", w)
+            }
+        }
+
+        impl WriteMagnitude for $t {
+            fn magnitude_len(self, f: &FormattedInt, sign: &str, close: &str) -> usize {
+                if f.pairs().is_none() && !f.digits.iter().all(char::is_ascii) {
+                    // The length depends on which digits come up, so just count them.
+                    return measure(|w| self.write_magnitude(f, sign, close, w));
+                }
+
+                let num_digits = self.num_digits(f.digits.len() as $t);
+
+                f.ascii_len(f.fit(num_digits, sign, close).unwrap_or(num_digits), sign, close)
+            }
+
+            fn write_magnitude<W: io::Write>(self, f: &FormattedInt, sign: &str, close: &str,
+                                             w: &mut W) -> io::Result<usize> {
+                let mut written = 0;
+
+                let base = f.digits.len() as $t;
+
+                let mut utf8_w = Utf8Write(w);
+
+                let all_digits = self.num_digits(base);
+                let num_digits = f.fit(all_digits, sign, close)?;
+
+                // Pad with `f.pad` until the minimum width is reached.
+                let padding = f.padding(num_digits, sign, close);
+
+                // How many grouped digits are still to be written, for placing separators.
+                let mut left = f.grouped_len(num_digits, padding);
+
+                written += f.push_padding(&mut utf8_w, PadPosition::BeforeSign, padding)?;
+                written += utf8_w.push_str(sign)?;
+                written += f.push_padding(&mut utf8_w, PadPosition::AfterSign, padding)?;
+                written += utf8_w.push_str(f.prefix)?;
+
+                if f.pad_at == PadPosition::AfterPrefix {
+                    for _ in 0..padding {
+                        written += utf8_w.push(f.pad)?;
+
+                        if f.grouped_padding() {
+                            left -= 1;
+                            written += f.push_separator(&mut utf8_w, left)?;
+                        }
+                    }
+                }
+
+                if let Some(pairs) = f.pairs().filter(|_| num_digits == all_digits) {
+                    // Fill the buffer from the end, two digits per division.
+                    let mut buf = [0u8; 40];
+                    let mut i   = buf.len();
+                    let mut r   = self;
+
+                    let base2 = base * base;
+
+                    while r >= base2 {
+                        let d = (r % base2) as usize * 2;
+                        r /= base2;
+                        i -= 2;
+                        buf[i..i + 2].copy_from_slice(&pairs[d..d + 2]);
+                    }
+
+                    if r >= base {
+                        let d = r as usize * 2;
+                        i -= 2;
+                        buf[i..i + 2].copy_from_slice(&pairs[d..d + 2]);
+                    } else {
+                        i -= 1;
+                        buf[i] = pairs[r as usize * 2 + 1];
+                    }
+
+                    if f.uppercase {
+                        buf[i..].make_ascii_uppercase();
+                    }
+
+                    utf8_w.0.write_all(&buf[i..])?;
+
+                    written += buf.len() - i;
+                } else {
+                    // Work the digits out from the least significant end, one division each, then
+                    // write them from the most significant. No type has more than 128 digits, and
+                    // when some are truncated this keeps the low-order ones.
+                    let mut buf = [0u32; 128];
+                    let mut r   = self;
+
+                    for d in buf[..num_digits].iter_mut().rev() {
+                        *d = (r % base) as u32;
+                        r /= base;
+                    }
+
+                    for &d in &buf[..num_digits] {
+                        written += utf8_w.push(f.digit(d as usize))?;
+
+                        left -= 1;
+                        written += f.push_separator(&mut utf8_w, left)?;
+                    }
+                }
+
+                Ok(written + utf8_w.push_str(f.suffix)? + utf8_w.push_str(close)?)
+            }
+        }
+    )*);
+
+    (@signed $($t:ty => $u:ty),*) => ($(
+        impl<'x> Format<$t> for FormattedInt<'x> {
+            fn len(&self, x: &$t) -> usize {
+                let (sign, close) = match self.sign {
+                    _ if *x >= 0       => return Format::len(self, &(*x as $u)),
+                    SignPolicy::Parens => ("(", ")"),
+                    _                  => ("-", ""),
+                };
+
+                x.unsigned_abs().magnitude_len(self, sign, close)
+            }
+
+            fn write<W: io::Write>(&self, x: &$t, w: &mut W) -> io::Result<usize> {
+                let (sign, close) = match self.sign {
+                    _ if *x >= 0       => return Format::write(self, &(*x as $u), w),
+                    SignPolicy::Parens => ("(", ")"),
+                    _                  => ("-", ""),
+                };
+
+                x.unsigned_abs().write_magnitude(self, sign, close, w)
+            }
+        }
+    )*);
+
+    (@widen $($t:ty => $wide:ty),*) => ($(
+        impl<'x> Format<$t> for FormattedInt<'x> {
+            fn len(&self, x: &$t) -> usize { Format::len(self, &(*x as $wide)) }
+            fn write<W: io::Write>(&self, x: &$t, w: &mut W) -> io::Result<usize> {
+                Format::write(self, &(*x as $wide), w)
+            }
+        }
+    )*);
+
+    (@show $($t:ty),*) => ($(
+        impl Show for $t {
+            fn len(&self) -> usize { Format::len(&FormattedInt::decimal(), self) }
+            fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+                Format::write(&FormattedInt::decimal(), self, w)
+            }
+        }
+    )*);
 }
 
+impl_int!(@unsigned u64, u128);
+impl_int!(@signed i64 => u64, i128 => u128);
+impl_int!(@widen u8 => u64, u16 => u64, u32 => u64, usize => u64,
+                 i8 => i64, i16 => i64, i32 => i64, isize => i64);
+impl_int!(@show u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+// Bytes and 16-bit counters are common enough to skip `FormattedInt` for: every digit is worked out
+// into a small array, then the leading zeros are left off.
 impl Show for u8 {
-    fn len(&self) -> usize { Show::len(&(*self as u64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as u64), w)
+    fn len(&self) -> usize {
+        match *self {
+            100.. => 3,
+            10..  => 2,
+            _     => 1,
+        }
     }
-}
 
-impl Show for usize {
-    fn len(&self) -> usize { Show::len(&(*self as u64)) }
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as u64), w)
+        let x   = *self;
+        let buf = [b'0' + x / 100, b'0' + x / 10 % 10, b'0' + x % 10];
+        let len = self.len();
+
+        w.write_all(&buf[3 - len..]).map(|()| len)
     }
 }
 
-impl Show for i64 {
+impl Show for u16 {
     fn len(&self) -> usize {
-        Format::len(&FormattedInt {
-            prefix:  "",
-            suffix:  "",
-            digits:  DECIMAL_DIGITS,
-            min_len: 0,
-            sign:    SignPolicy::Empty,
-        }, self)
+        match *self {
+            10000.. => 5,
+            1000..  => 4,
+            100..   => 3,
+            10..    => 2,
+            _       => 1,
+        }
     }
 
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Format::write(&FormattedInt {
-            prefix:  "",
-            suffix:  "",
-            digits:  DECIMAL_DIGITS,
-            min_len: 0,
-            sign:    SignPolicy::Empty,
-        }, self, w)
+        let x   = *self;
+        let buf = [
+            b'0' + (x / 10000) as u8,
+            b'0' + (x / 1000 % 10) as u8,
+            b'0' + (x / 100 % 10) as u8,
+            b'0' + (x / 10 % 10) as u8,
+            b'0' + (x % 10) as u8,
+        ];
+        let len = self.len();
+
+        w.write_all(&buf[5 - len..]).map(|()| len)
     }
 }
 
-impl Show for i32 {
-    fn len(&self) -> usize { Show::len(&(*self as i64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as i64), w)
-    }
-}
+pub const DECIMAL_DIGITS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
 
-impl Show for i16 {
-    fn len(&self) -> usize { Show::len(&(*self as i64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as i64), w)
-    }
-}
+const HEX_DIGITS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+                                       'a', 'b', 'c', 'd', 'e', 'f'];
 
-impl Show for i8 {
-    fn len(&self) -> usize { Show::len(&(*self as i64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as i64), w)
-    }
-}
+// Every two-digit string in a base, in order, so the digits of `n` are at `2 * n`.
+const fn digit_pairs<const N: usize>(digits: &[char]) -> [u8; N] {
+    let base      = digits.len();
+    let mut pairs = [0; N];
+    let mut n     = 0;
 
-impl Show for isize {
-    fn len(&self) -> usize { Show::len(&(*self as i64)) }
-    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Show::write(&(*self as i64), w)
+    while n < base * base {
+        pairs[2 * n]     = digits[n / base] as u8;
+        pairs[2 * n + 1] = digits[n % base] as u8;
+        n += 1;
     }
+
+    pairs
 }
 
+const DECIMAL_PAIRS: [u8; 200] = digit_pairs(DECIMAL_DIGITS);
+const HEX_PAIRS:     [u8; 512] = digit_pairs(HEX_DIGITS);
+
+pub const HEX: FormattedInt<'static> = FormattedInt::hex();
+
+/// Like `HEX`, but with uppercase letters.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::UPPER_HEX;
+/// # fn main() {
+/// assert_eq!(scat!(UPPER_HEX; 0xbeefu32), "BEEF");
+/// # }
+/// ```
+pub const UPPER_HEX: FormattedInt<'static> = FormattedInt::hex().upper();
+
+/// Arabic-Indic digits, as written in Arabic.
+pub const ARABIC_INDIC_DIGITS: &[char] = &['٠', '١', '٢', '٣', '٤', '٥', '٦', '٧', '٨', '٩'];
+
+/// Eastern Arabic-Indic digits, as written in Persian and Urdu.
+pub const EASTERN_ARABIC_INDIC_DIGITS: &[char] =
+    &['۰', '۱', '۲', '۳', '۴', '۵', '۶', '۷', '۸', '۹'];
+
+/// Devanagari digits, as written in Hindi, Marathi and Nepali.
+pub const DEVANAGARI_DIGITS: &[char] = &['०', '१', '२', '३', '४', '५', '६', '७', '८', '९'];
+
+/// Base-10 in Arabic-Indic digits. Its padding and sign work the same as `decimal()`'s, and its
+/// `min_len` counts digits, not bytes.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{ARABIC_INDIC, EASTERN_ARABIC_INDIC, DEVANAGARI};
+/// # fn main() {
+/// assert_eq!(scat!(ARABIC_INDIC; 2024), "٢٠٢٤");
+/// assert_eq!(scat!(EASTERN_ARABIC_INDIC.min_len(3); -7), "-۰۰۷");
+/// assert_eq!(scat!(DEVANAGARI.group(',', 3); 1234567u32), "१,२३४,५६७");
+/// # }
+/// ```
+pub const ARABIC_INDIC: FormattedInt<'static> = FormattedInt::with_digits(ARABIC_INDIC_DIGITS);
+
+/// Base-10 in Eastern Arabic-Indic digits. See `ARABIC_INDIC`.
+pub const EASTERN_ARABIC_INDIC: FormattedInt<'static> =
+    FormattedInt::with_digits(EASTERN_ARABIC_INDIC_DIGITS);
+
+/// Base-10 in Devanagari digits. See `ARABIC_INDIC`.
+pub const DEVANAGARI: FormattedInt<'static> = FormattedInt::with_digits(DEVANAGARI_DIGITS);
+
 impl Show for str {
     fn len(&self) -> usize { self.len() }
+    fn stored_bytes(&self) -> Option<&[u8]> { Some(self.as_bytes()) }
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Utf8Write(w).push_str(self)
+        w.write_all(self.as_bytes()).map(|()| self.len())
     }
 }
 
-impl<'x, T: ?Sized> Show for &'x T where T: Show {
+impl<T: ?Sized> Show for &T where T: Show {
     fn len(&self) -> usize { Show::len(*self) }
+    fn size_hint(&self) -> SizeHint { Show::size_hint(*self) }
+    fn stored_bytes(&self) -> Option<&[u8]> { Show::stored_bytes(*self) }
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
         Show::write(*self, w)
     }
@@ -337,8 +964,9 @@ impl<'x, T: ?Sized> Show for &'x T where T: Show {
 
 impl Show for String {
     fn len(&self) -> usize { self.len() }
+    fn stored_bytes(&self) -> Option<&[u8]> { Some(self.as_bytes()) }
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        Utf8Write(w).push_str(&self[..])
+        Show::write(self.as_str(), w)
     }
 }
 
@@ -349,8 +977,9 @@ impl Show for char {
     }
 }
 
-impl<'x, T: ?Sized, U> Format<U> for &'x T where T: Format<U> {
+impl<T: ?Sized, U> Format<U> for &T where T: Format<U> {
     fn len(&self, u: &U) -> usize { Format::len(*self, u) }
+    fn size_hint(&self, u: &U) -> SizeHint { Format::size_hint(*self, u) }
     fn write<W: io::Write>(&self, u: &U, w: &mut W) -> io::Result<usize> {
         Format::write(*self, u, w)
     }
@@ -360,30 +989,138 @@ pub struct Rep(pub usize);
 
 impl<T> Format<T> for Rep
 where T: Show {
-    fn len(&self, t: &T) -> usize { self.0 * Show::len(t) }
+    fn len(&self, t: &T) -> usize { self.0.saturating_mul(Show::len(t)) }
     fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
         let mut len = 0;
         for _ in 0..self.0 {
-            len += try!(Show::write(t, w));
+            len += Show::write(t, w)?;
         }
 
         Ok(len)
     }
 }
 
+/// Like `Rep`, but with `sep` shown between the copies. Wrap an inner format with `Compose` to
+/// repeat its output instead of the plain value.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::{RepSep, Compose, HEX};
+/// # fn main() {
+/// assert_eq!(scat!(RepSep(4, ' '); '-'), "- - - -");
+/// assert_eq!(scat!(Compose(RepSep(3, ", "), HEX); 255u8), "ff, ff, ff");
+/// assert_eq!(scat!(RepSep(0, ' '); '-'), "");
+/// # }
+/// ```
+pub struct RepSep<S>(pub usize, pub S);
+
+impl<T, S> Format<T> for RepSep<S>
+where T: Show, S: Show {
+    fn len(&self, t: &T) -> usize {
+        let seps = self.0.saturating_sub(1).saturating_mul(Show::len(&self.1));
+
+        self.0.saturating_mul(Show::len(t)).saturating_add(seps)
+    }
+
+    fn write<W: io::Write>(&self, t: &T, w: &mut W) -> io::Result<usize> {
+        let mut len = 0;
+        for i in 0..self.0 {
+            if i > 0 {
+                len += Show::write(&self.1, w)?;
+            }
+
+            len += Show::write(t, w)?;
+        }
+
+        Ok(len)
+    }
+}
+
+/// A value paired with the format to show it in. This lets a formatted value go anywhere a `Show`
+/// is expected, such as inside another format.
+pub struct Formatted<'x, F, T: 'x>(pub F, pub &'x T);
+
+impl<'x, F, T> Show for Formatted<'x, F, T>
+where F: Format<T> {
+    fn len(&self) -> usize { Format::len(&self.0, self.1) }
+    fn size_hint(&self) -> SizeHint { Format::size_hint(&self.0, self.1) }
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Format::write(&self.0, self.1, w)
+    }
+}
+
 impl<T> Show for Option<T>
 where T: Show {
     fn len(&self) -> usize {
-        match self {
-            &Some(ref t) => t.len(),
-            &None        => 0,
+        match *self {
+            Some(ref t) => t.len(),
+            None        => 0,
+        }
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        match *self {
+            Some(ref t) => t.size_hint(),
+            None        => SizeHint::Exact(0),
         }
     }
 
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
-        match self {
-            &Some(ref t) => t.write(w),
-            &None        => Ok(0),
+        match *self {
+            Some(ref t) => t.write(w),
+            None        => Ok(0),
         }
     }
 }
+
+/// An object-safe version of `Show`, so that values of different types can be kept together as
+/// `Box<dyn ShowDyn>` or `&dyn ShowDyn` and still be catted. Every sized `Show` implements it.
+///
+/// # Examples
+/// ```
+/// # #[macro_use] extern crate cats;
+/// # use cats::ShowDyn;
+/// # fn main() {
+/// let parts: Vec<Box<dyn ShowDyn>> = vec![Box::new("lives: "), Box::new(9), Box::new('!')];
+///
+/// assert_eq!(scat!(parts[0], parts[1], parts[2]), "lives: 9!");
+/// # }
+/// ```
+pub trait ShowDyn {
+    /// Same as `Show::len`.
+    fn len_dyn(&self) -> usize;
+
+    /// Same as `Show::size_hint`.
+    fn size_hint_dyn(&self) -> SizeHint;
+
+    /// Same as `Show::write`, but through a trait object.
+    fn write_dyn(&self, w: &mut dyn io::Write) -> io::Result<usize>;
+}
+
+impl<T> ShowDyn for T
+where T: Show {
+    fn len_dyn(&self) -> usize { Show::len(self) }
+    fn size_hint_dyn(&self) -> SizeHint { Show::size_hint(self) }
+    fn write_dyn(&self, mut w: &mut dyn io::Write) -> io::Result<usize> {
+        Show::write(self, &mut w)
+    }
+}
+
+impl<'a> Show for dyn ShowDyn + 'a {
+    fn len(&self) -> usize { self.len_dyn() }
+    fn size_hint(&self) -> SizeHint { self.size_hint_dyn() }
+    fn write<W: io::Write>(&self, mut w: &mut W) -> io::Result<usize> {
+        self.write_dyn(&mut w)
+    }
+}
+
+impl<T: ?Sized> Show for Box<T>
+where T: Show {
+    fn len(&self) -> usize { Show::len(&**self) }
+    fn size_hint(&self) -> SizeHint { Show::size_hint(&**self) }
+    fn stored_bytes(&self) -> Option<&[u8]> { Show::stored_bytes(&**self) }
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<usize> {
+        Show::write(&**self, w)
+    }
+}
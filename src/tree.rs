@@ -0,0 +1,172 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! Trees drawn with guide lines, like `tree` and `cargo tree` print them.
+//!
+//! Anything shaped like a tree can be drawn by implementing `TreeNode` for its nodes, and then
+//! catting a node with `Guides`:
+//!
+//! ```text
+//! src
+//! ├── lib.rs
+//! └── table
+//!     ├── mod.rs
+//!     └── border.rs
+//! ```
+//!
+//! There's no newline after the last line, so a tree goes straight into `catln!`. A label that
+//! spans several lines keeps to its own column, under the guide lines of its ancestors.
+//!
+//! # Examples
+//! ```
+//! # #[macro_use] extern crate cats;
+//! use cats::tree::{Guides, TreeNode};
+//!
+//! struct File {
+//!     name:     &'static str,
+//!     children: Vec<File>,
+//! }
+//!
+//! impl TreeNode for File {
+//!     type Label = str;
+//!
+//!     fn label(&self) -> &str { self.name }
+//!     fn children(&self) -> Vec<&File> { self.children.iter().collect() }
+//! }
+//!
+//! fn file(name: &'static str, children: Vec<File>) -> File {
+//!     File { name, children }
+//! }
+//!
+//! # fn main() {
+//! let src = file("src", vec![
+//!     file("lib.rs", vec![]),
+//!     file("table", vec![file("mod.rs", vec![]), file("border.rs", vec![])]),
+//! ]);
+//!
+//! assert_eq!(scat!(Guides::Light; src), "\
+//! src
+//! ├── lib.rs
+//! └── table
+//!     ├── mod.rs
+//!     └── border.rs");
+//!
+//! assert_eq!(scat!(Guides::Ascii; src.children[1]), "\
+//! table
+//! |-- mod.rs
+//! `-- border.rs");
+//! # }
+//! ```
+
+use std::io;
+
+use traits::{Show, Format, measure, write_counted};
+
+/// A node of a tree that `Guides` can draw.
+pub trait TreeNode {
+    /// What's shown for the node.
+    type Label: Show + ?Sized;
+
+    fn label(&self) -> &Self::Label;
+
+    /// The node's children, in the order they're drawn.
+    fn children(&self) -> Vec<&Self>;
+}
+
+/// The lines that join a node to its children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Guides {
+    /// `├──`, `└──` and `│`.
+    Light,
+    /// Like `Light`, but the last child is joined with a rounded `╰──`.
+    Rounded,
+    /// `|--`, `` `-- `` and `|`, for terminals without box-drawing characters.
+    Ascii,
+}
+
+impl Guides {
+    // What goes in front of a child, and in front of everything under it, depending on whether
+    // it's the last of its siblings.
+    fn pieces(self, last: bool) -> (&'static str, &'static str) {
+        match (self, last) {
+            (Guides::Light, false) | (Guides::Rounded, false) => ("├── ", "│   "),
+            (Guides::Light, true)                             => ("└── ", "    "),
+            (Guides::Rounded, true)                           => ("╰── ", "    "),
+            (Guides::Ascii, false)                            => ("|-- ", "|   "),
+            (Guides::Ascii, true)                             => ("`-- ", "    "),
+        }
+    }
+
+    // Write a node's label, starting each line after the first with `indent`.
+    fn label<T, W>(node: &T, indent: &str, w: &mut W) -> io::Result<()>
+    where T: TreeNode, W: io::Write {
+        let label   = node.label();
+        let mut buf = Vec::with_capacity(label.len());
+
+        label.write(&mut buf)?;
+
+        for (i, line) in buf.split(|&b| b == b'\n').enumerate() {
+            if i > 0 {
+                w.write_all(b"\n")?;
+                w.write_all(indent.as_bytes())?;
+            }
+
+            w.write_all(line)?;
+        }
+
+        Ok(())
+    }
+
+    // Write the descendants of `node`, each on a new line after `prefix`.
+    fn children<T, W>(self, node: &T, prefix: &mut String, w: &mut W) -> io::Result<()>
+    where T: TreeNode, W: io::Write {
+        let children = node.children();
+        let count    = children.len();
+
+        for (i, child) in children.into_iter().enumerate() {
+            let (branch, stem) = self.pieces(i + 1 == count);
+            let len            = prefix.len();
+
+            w.write_all(b"\n")?;
+            w.write_all(prefix.as_bytes())?;
+            w.write_all(branch.as_bytes())?;
+
+            prefix.push_str(stem);
+            Guides::label(child, prefix, w)?;
+            self.children(child, prefix, w)?;
+            prefix.truncate(len);
+        }
+
+        Ok(())
+    }
+
+    fn lay_out<T, W>(self, root: &T, w: &mut W) -> io::Result<()>
+    where T: TreeNode, W: io::Write {
+        Guides::label(root, "", w)?;
+        self.children(root, &mut String::new(), w)
+    }
+}
+
+impl<T> Format<T> for Guides
+where T: TreeNode {
+    fn len(&self, root: &T) -> usize {
+        measure(|w| self.lay_out(root, w))
+    }
+
+    fn write<W: io::Write>(&self, root: &T, w: &mut W) -> io::Result<usize> {
+        write_counted(w, |w| self.lay_out(root, w))
+    }
+}
@@ -0,0 +1,108 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+use std::io::{self, IoSlice};
+
+use traits::{Show, Format};
+
+enum Piece<'a> {
+    Borrowed(&'a [u8]),
+    // A range of `__Gather::scratch`. It can't be borrowed until everything has been rendered,
+    // since the scratch buffer may move as it grows.
+    Scratch(usize, usize),
+}
+
+// Collects the segments of an `fcat_vectored!`. Segments that already hold their bytes are
+// borrowed, everything else is rendered into one scratch buffer, and the whole lot goes to the
+// writer in as few `write_vectored` calls as it will take.
+#[doc(hidden)]
+pub struct __Gather<'a> {
+    pieces:  Vec<Piece<'a>>,
+    scratch: Vec<u8>,
+    error:   Option<io::Error>,
+}
+
+impl<'a> __Gather<'a> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> __Gather<'a> {
+        __Gather { pieces: Vec::new(), scratch: Vec::new(), error: None }
+    }
+
+    pub fn show<T: ?Sized + Show>(&mut self, t: &'a T) {
+        match t.stored_bytes() {
+            Some(bytes) => self.pieces.push(Piece::Borrowed(bytes)),
+            None        => self.render(|buf| t.write(buf)),
+        }
+    }
+
+    pub fn format<F: ?Sized + Format<T>, T>(&mut self, f: &F, t: &T) {
+        self.render(|buf| f.write(t, buf))
+    }
+
+    fn render<R>(&mut self, write: R)
+    where R: FnOnce(&mut Vec<u8>) -> io::Result<usize> {
+        let start = self.scratch.len();
+
+        if let Err(e) = write(&mut self.scratch) {
+            self.error.get_or_insert(e);
+        }
+
+        // Rendered segments that follow one another are already contiguous in the scratch buffer.
+        match self.pieces.last_mut() {
+            Some(&mut Piece::Scratch(_, ref mut end)) if *end == start => *end = self.scratch.len(),
+            _ => self.pieces.push(Piece::Scratch(start, self.scratch.len())),
+        }
+    }
+
+    pub fn write_to<W: io::Write>(self, w: &mut W) -> io::Result<usize> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        // Empty segments are dropped first, so that a writer which has nothing to do isn't taken
+        // for a full one.
+        let mut bytes: Vec<&[u8]> = self.pieces.iter().map(|piece| match *piece {
+            Piece::Borrowed(bytes)     => bytes,
+            Piece::Scratch(start, end) => &self.scratch[start..end],
+        }).filter(|bytes| !bytes.is_empty()).collect();
+
+        let total     = bytes.iter().map(|b| b.len()).sum();
+        let mut first = 0;
+
+        // This is `write_all_vectored`, which isn't stable yet.
+        while first < bytes.len() {
+            let slices: Vec<IoSlice> = bytes[first..].iter().map(|b| IoSlice::new(b)).collect();
+
+            match w.write_vectored(&slices) {
+                Ok(0)     => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(mut n) => while n > 0 {
+                    let len = bytes[first].len();
+
+                    if n < len {
+                        bytes[first] = &bytes[first][n..];
+                        break;
+                    }
+
+                    n     -= len;
+                    first += 1;
+                },
+                Err(e)    => if e.kind() != io::ErrorKind::Interrupted { return Err(e) },
+            }
+        }
+
+        Ok(total)
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+use std::any;
+use std::io;
+
+use traits::{Show, Format};
+
+/// An `io::Write` adapter that keeps count of the bytes written through it, and can check that a
+/// `Show` impl keeps its side of the contract: that `len` is the number of bytes `write` puts out,
+/// and that `write` returns that same number.
+///
+/// Wrapping the writer handed to a `Show` impl from another crate turns a broken impl into an
+/// error where it happens, instead of a short or overlong buffer somewhere further on.
+///
+/// # Examples
+/// ```
+/// # use cats::{CatWriter, Show};
+/// # use std::io::{self, Write};
+/// struct Liar;
+///
+/// impl Show for Liar {
+///     fn len(&self) -> usize { 3 }
+///     fn write<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+///         w.write_all(b"meow").map(|()| 3)
+///     }
+/// }
+///
+/// let mut w = CatWriter::new(Vec::new());
+///
+/// assert_eq!(w.show("Tom").unwrap(), 3);
+/// assert_eq!(w.show(&Liar).unwrap_err().kind(), io::ErrorKind::InvalidData);
+/// assert_eq!(w.written(), 7);
+/// ```
+pub struct CatWriter<W: io::Write> {
+    inner:   W,
+    written: usize,
+}
+
+impl<W: io::Write> CatWriter<W> {
+    pub fn new(inner: W) -> CatWriter<W> {
+        CatWriter { inner, written: 0 }
+    }
+
+    /// The number of bytes that have gone through to the inner writer so far.
+    pub fn written(&self) -> usize { self.written }
+
+    pub fn get_ref(&self) -> &W { &self.inner }
+
+    pub fn get_mut(&mut self) -> &mut W { &mut self.inner }
+
+    pub fn into_inner(self) -> W { self.inner }
+
+    /// Write `t` and return the number of bytes it took. If that isn't what `t.len()` promised,
+    /// or `t.write` reports a different number, this fails with `ErrorKind::InvalidData` naming
+    /// the type. The bytes have already been written by then.
+    pub fn show<T: ?Sized + Show>(&mut self, t: &T) -> io::Result<usize> {
+        let promised = t.len();
+        let before   = self.written;
+        let reported = t.write(self)?;
+
+        check::<T>(promised, self.written - before, reported)
+            .map_err(|msg| io::Error::new(io::ErrorKind::InvalidData, msg))
+    }
+}
+
+// Make sure one `write` put out as many bytes as `len` promised, and said so.
+fn check<T: ?Sized>(promised: usize, actual: usize, reported: usize) -> Result<usize, String> {
+    if promised == actual && reported == actual {
+        return Ok(actual);
+    }
+
+    Err(format!("`{}` promised {} bytes from `len`, wrote {} and reported {}",
+                any::type_name::<T>(), promised, actual, reported))
+}
+
+// `cat_write!` writes each segment through one of these. In debug builds they check the segment
+// against its `len` and panic if it doesn't match, since a broken impl would otherwise show up
+// later as a short or overlong buffer in some other cat.
+#[doc(hidden)]
+#[track_caller]
+pub fn __write_show<T, W>(t: &T, w: &mut W) -> io::Result<usize>
+where T: ?Sized + Show, W: io::Write {
+    if !cfg!(debug_assertions) {
+        return t.write(w);
+    }
+
+    let promised = t.len();
+    let mut w    = CatWriter::new(w);
+    let reported = t.write(&mut w)?;
+
+    match check::<T>(promised, w.written(), reported) {
+        Ok(n)    => Ok(n),
+        Err(msg) => panic!("{}", msg),
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __write_format<F, T, W>(f: &F, t: &T, w: &mut W) -> io::Result<usize>
+where F: ?Sized + Format<T>, W: io::Write {
+    if !cfg!(debug_assertions) {
+        return f.write(t, w);
+    }
+
+    let promised = f.len(t);
+    let mut w    = CatWriter::new(w);
+    let reported = f.write(t, &mut w)?;
+
+    match check::<F>(promised, w.written(), reported) {
+        Ok(n)    => Ok(n),
+        Err(msg) => panic!("{}", msg),
+    }
+}
+
+impl<W: io::Write> io::Write for CatWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n;
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> { self.inner.flush() }
+}
+
+// Compiles only if `t` can be shown, for literals that `cat_write!` joins with `concat!`.
+#[doc(hidden)]
+#[inline(always)]
+pub fn __show_literal<T: ?Sized + Show>(_: &T) {}
+
+// How many bytes `fcat!` gathers before writing them out.
+const BATCH: usize = 512;
+
+// Gathers small writes in a buffer on the stack and passes them on together, so that an
+// unbuffered `File` or stderr sees a handful of writes per cat rather than one per argument. Writes
+// too big for the buffer go straight through. Whatever is left is written out by `finish`, or on
+// drop if a later argument failed.
+#[doc(hidden)]
+pub struct __Batch<W: io::Write> {
+    inner: W,
+    buf:   [u8; BATCH],
+    len:   usize,
+}
+
+impl<W: io::Write> __Batch<W> {
+    pub fn new(inner: W) -> __Batch<W> {
+        __Batch { inner, buf: [0; BATCH], len: 0 }
+    }
+
+    fn write_buf(&mut self) -> io::Result<()> {
+        let len = self.len;
+
+        self.len = 0;
+        self.inner.write_all(&self.buf[..len])
+    }
+
+    pub fn finish(mut self) -> io::Result<()> { self.write_buf() }
+}
+
+impl<W: io::Write> io::Write for __Batch<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if self.len + data.len() > BATCH {
+            self.write_buf()?;
+        }
+
+        if data.len() >= BATCH {
+            return self.inner.write(data);
+        }
+
+        self.buf[self.len..self.len + data.len()].copy_from_slice(data);
+        self.len += data.len();
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_buf()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: io::Write> Drop for __Batch<W> {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            let _ = self.write_buf();
+        }
+    }
+}
@@ -0,0 +1,254 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! Every `Show` and `Format` promises that `len` is exactly the number of bytes `write` puts out,
+//! and that those bytes are valid utf-8. These check that promise against random values and
+//! random `FormattedInt` settings, to reach the corners that the examples don't: multi-byte
+//! digits and padding, the most negative value of each type, grouping and every padding position.
+
+#[macro_use] extern crate proptest;
+#[macro_use] extern crate cats;
+
+use std::io;
+use std::str;
+
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseError;
+
+use cats::{Align, FieldOverflow, Formatted, FormattedInt, Overflow, Pad, PadPosition, ScatError,
+           Show, SignPolicy, SizeHint};
+
+const DECIMAL: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+const HEX:     &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9', 'a', 'b', 'c', 'd',
+                           'e', 'f'];
+const BINARY:  &[char] = &['0', '1'];
+const ARABIC:  &[char] = &['٠', '١', '٢', '٣', '٤', '٥', '٦', '٧', '٨', '٩'];
+const KANJI:   &[char] = &['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+const MIXED:   &[char] = &['𝟎', 'x', '🐈'];
+
+// The `FieldOverflow` that `e` wraps, if it wraps one.
+fn overflow_of(e: &io::Error) -> Option<FieldOverflow> {
+    e.get_ref().and_then(|e| e.downcast_ref::<FieldOverflow>()).cloned()
+}
+
+// Write `t` and check the output against what it promised.
+fn check<T: Show>(t: &T) -> Result<(), TestCaseError> {
+    let mut out = Vec::new();
+    let written = match t.write(&mut out) {
+        Ok(n)  => n,
+        Err(e) => match overflow_of(&e) {
+            Some(overflow) => return check_overflow(t, overflow),
+            None           => return Err(e.into()),
+        },
+    };
+
+    prop_assert_eq!(t.len(), out.len(), "len() disagrees with the bytes written");
+    prop_assert_eq!(written, out.len(), "write() misreported the bytes written");
+    prop_assert!(str::from_utf8(&out).is_ok(), "wrote invalid utf-8: {:?}", out);
+
+    match t.size_hint() {
+        SizeHint::Exact(n)   => prop_assert_eq!(n, out.len()),
+        SizeHint::AtLeast(n) => prop_assert!(n <= out.len()),
+    }
+
+    Ok(())
+}
+
+// A value too wide for a field that refuses to grow has nothing to compare, but every way of
+// rendering it into memory must report the same overflow instead of dropping it.
+fn check_overflow<T: Show>(t: &T, overflow: FieldOverflow) -> Result<(), TestCaseError> {
+    prop_assert!(overflow.chars > overflow.width, "reported {:?} for a value that fits", overflow);
+
+    let mut out = Vec::new();
+
+    match t.write_to(&mut out) {
+        Err(ref e) => prop_assert_eq!(overflow_of(e), Some(overflow)),
+        Ok(_)      => prop_assert!(false, "write_to() succeeded after write() overflowed"),
+    }
+
+    prop_assert!(out.is_empty(), "write_to() wrote part of a value that overflowed");
+
+    match try_scat!(*t) {
+        Err(ScatError::Write(ref e)) => prop_assert_eq!(overflow_of(e), Some(overflow)),
+        other => prop_assert!(false, "try_scat! gave {:?} after write() overflowed", other),
+    }
+
+    Ok(())
+}
+
+fn affix() -> impl Strategy<Value = &'static str> {
+    prop_oneof![Just(""), Just("0x"), Just("#"), Just("€"), Just(" km"), Just("🐈🐈")]
+}
+
+// A fill or separator, of one to four bytes.
+fn any_char() -> impl Strategy<Value = char> {
+    prop_oneof![Just(' '), Just('_'), Just('·'), Just('█'), Just('🐈'), any::<char>()]
+}
+
+// The settings for a `FormattedInt`, kept apart from it so that a failing case can be printed.
+#[derive(Debug)]
+struct IntSettings {
+    digits:     &'static [char],
+    prefix:     &'static str,
+    suffix:     &'static str,
+    min_len:    usize,
+    pad:        Option<char>,
+    sign:       usize,
+    group:      Option<(char, usize)>,
+    uppercase:  bool,
+    pad_at:     usize,
+    full_width: bool,
+    overflow:   usize,
+}
+
+const PAD_ATS:   [PadPosition; 3] = [PadPosition::BeforeSign, PadPosition::AfterSign,
+                                     PadPosition::AfterPrefix];
+const ALIGNS:    [Align; 3]       = [Align::Left, Align::Right, Align::Center];
+const OVERFLOWS: [Overflow; 3]    = [Overflow::Expand, Overflow::Truncate, Overflow::Error];
+
+impl IntSettings {
+    fn build(&self) -> FormattedInt<'static> {
+        let mut f = FormattedInt::with_digits(self.digits)
+            .prefix(self.prefix)
+            .suffix(self.suffix)
+            .min_len(self.min_len)
+            .sign(match self.sign {
+                0 => SignPolicy::Plus,
+                1 => SignPolicy::Space,
+                2 => SignPolicy::Empty,
+                _ => SignPolicy::Parens,
+            })
+            .pad_at(PAD_ATS[self.pad_at])
+            .overflow(OVERFLOWS[self.overflow]);
+
+        if let Some(pad) = self.pad {
+            f = f.pad(pad);
+        }
+
+        if let Some((sep, size)) = self.group {
+            f = f.group(sep, size);
+        }
+
+        if self.uppercase {
+            f = f.upper();
+        }
+
+        if self.full_width {
+            f = f.full_width();
+        }
+
+        f
+    }
+}
+
+prop_compose! {
+    fn int_settings()(digits     in prop_oneof![Just(DECIMAL), Just(HEX), Just(BINARY),
+                                                Just(ARABIC), Just(KANJI), Just(MIXED)],
+                      prefix     in affix(),
+                      suffix     in affix(),
+                      min_len    in 0..48usize,
+                      pad        in prop::option::of(any_char()),
+                      sign       in 0..4usize,
+                      group      in prop::option::of((any_char(), 0..5usize)),
+                      uppercase  in any::<bool>(),
+                      pad_at     in 0..PAD_ATS.len(),
+                      full_width in any::<bool>(),
+                      overflow   in 0..OVERFLOWS.len())
+                      -> IntSettings {
+        IntSettings {
+            digits, prefix, suffix, min_len, pad, sign, group, uppercase, pad_at, full_width,
+            overflow,
+        }
+    }
+}
+
+// A value of `$t` that's an edge case as often as not.
+macro_rules! int {
+    ($t:ty) => (prop_oneof![Just(<$t>::MIN), Just(<$t>::MAX), Just(0 as $t), any::<$t>()])
+}
+
+macro_rules! int_tests {
+    ($($name:ident: $t:ty,)*) => ($(
+        mod $name {
+            use super::*;
+
+            proptest! {
+                #[test]
+                fn show(x in int!($t)) {
+                    check(&x)?;
+                }
+
+                #[test]
+                fn formatted(x in int!($t), f in int_settings()) {
+                    check(&Formatted(f.build(), &x))?;
+                }
+            }
+        }
+    )*)
+}
+
+int_tests! {
+    int_u8:    u8,
+    int_u16:   u16,
+    int_u32:   u32,
+    int_u64:   u64,
+    int_u128:  u128,
+    int_usize: usize,
+    int_i8:    i8,
+    int_i16:   i16,
+    int_i32:   i32,
+    int_i64:   i64,
+    int_i128:  i128,
+    int_isize: isize,
+}
+
+// A `Pad`'s width, fill, alignment and overflow, as indexes where they aren't printable.
+prop_compose! {
+    fn pad_settings()(width    in 0..48usize,
+                      fill     in any_char(),
+                      align    in 0..ALIGNS.len(),
+                      overflow in 0..OVERFLOWS.len())
+                      -> (usize, char, usize, usize) {
+        (width, fill, align, overflow)
+    }
+}
+
+fn pad((width, fill, align, overflow): (usize, char, usize, usize)) -> Pad {
+    Pad { width, fill, align: ALIGNS[align], overflow: OVERFLOWS[overflow] }
+}
+
+proptest! {
+    #[test]
+    fn strings(s in any::<String>()) {
+        check(&s)?;
+        check(&s.as_str())?;
+    }
+
+    #[test]
+    fn chars(c in any::<char>()) {
+        check(&c)?;
+    }
+
+    #[test]
+    fn padded(s in any::<String>(), p in pad_settings()) {
+        check(&Formatted(pad(p), &s))?;
+    }
+
+    #[test]
+    fn padded_ints(x in int!(i64), f in int_settings(), p in pad_settings()) {
+        check(&Formatted(pad(p), &Formatted(f.build(), &x)))?;
+    }
+}
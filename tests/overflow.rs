@@ -0,0 +1,160 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! A field whose `Overflow` is `Error` can make a cat into memory fail, even though memory itself
+//! never refuses a write. These check that each of the macros and methods that render into memory
+//! reports that failure, instead of unwrapping it or mistaking it for something else.
+
+#[macro_use] extern crate cats;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+
+use std::fmt::Write;
+
+use cats::{Align, BufferFull, FieldOverflow, Formatted, Overflow, Pad, ScatError, Show};
+
+const NAME: Pad = Pad { width: 4, fill: ' ', align: Align::Left, overflow: Overflow::Error };
+
+const OVERFLOW: FieldOverflow = FieldOverflow { width: 4, chars: 7 };
+
+fn overflow_of(e: &ScatError) -> Option<FieldOverflow> {
+    match *e {
+        ScatError::Write(ref e) => e.get_ref().and_then(|e| e.downcast_ref()).cloned(),
+        ScatError::Utf8(_)      => None,
+    }
+}
+
+#[test]
+fn try_scat_returns_the_overflow() {
+    assert_eq!(try_scat!(NAME; "Tom").unwrap(), "Tom ");
+    assert_eq!(try_scat!(NAME; "Mittens").as_ref().map_err(overflow_of), Err(Some(OVERFLOW)));
+}
+
+#[test]
+#[should_panic(expected = "scat! failed to write: value 7 characters wide doesn't fit in a field")]
+fn scat_panics_naming_the_overflow() {
+    scat!(NAME; "Mittens");
+}
+
+#[test]
+#[should_panic(expected = "scatln! failed to write: value 7 characters wide")]
+fn scatln_panics_naming_the_overflow() {
+    scatln!(NAME; "Mittens");
+}
+
+#[test]
+#[should_panic(expected = "vcat! failed to write: value 7 characters wide")]
+fn vcat_panics_naming_the_overflow() {
+    let mut buf = Vec::new();
+
+    vcat!(buf, NAME; "Mittens");
+}
+
+#[test]
+#[should_panic(expected = "bcat! failed to write: value 7 characters wide")]
+fn bcat_panics_naming_the_overflow() {
+    bcat!(NAME; "Mittens");
+}
+
+#[test]
+#[should_panic(expected = "strcat! failed to write: value 7 characters wide")]
+fn strcat_panics_naming_the_overflow() {
+    let mut s = String::from("Name: ");
+
+    strcat!(s, NAME; "Mittens");
+    assert_eq!(s, "Name: Mittens");
+}
+
+#[test]
+#[should_panic(expected = "scat_small! failed to write: value 7 characters wide")]
+fn scat_small_panics_naming_the_overflow() {
+    scat_small!(NAME; "Mittens");
+}
+
+#[test]
+#[should_panic(expected = "catf! failed to write: value 7 characters wide")]
+fn catf_panics_naming_the_overflow() {
+    catf!("{}", Formatted(NAME, &"Mittens"));
+}
+
+#[test]
+#[should_panic(expected = "Show::to_cat_string failed to write: value 7 characters wide")]
+fn to_cat_string_panics_naming_the_overflow() {
+    Formatted(NAME, &"Mittens").to_cat_string();
+}
+
+#[test]
+#[should_panic(expected = "Show::write_to_vec failed to write: value 7 characters wide")]
+fn write_to_vec_panics_naming_the_overflow() {
+    Formatted(NAME, &"Mittens").write_to_vec();
+}
+
+#[test]
+fn write_to_returns_the_overflow_and_writes_nothing() {
+    let mut out = Vec::new();
+    let err     = Formatted(NAME, &"Mittens").write_to(&mut out).unwrap_err();
+
+    assert_eq!(overflow_of(&ScatError::Write(err)), Some(OVERFLOW));
+    assert!(out.is_empty());
+}
+
+#[test]
+fn scat_into_slice_still_reports_a_full_buffer() {
+    let mut buf = [0u8; 6];
+
+    assert_eq!(scat_into_slice!(&mut buf, NAME; "Tom", '!'), Ok("Tom !"));
+    assert_eq!(scat_into_slice!(&mut buf, NAME; "Tom", "!!!"), Err(BufferFull));
+}
+
+#[test]
+#[should_panic(expected = "scat_into_slice! failed to write: value 7 characters wide")]
+fn scat_into_slice_panics_naming_the_overflow() {
+    let mut buf = [0u8; 64];
+
+    let _ = scat_into_slice!(&mut buf, NAME; "Mittens");
+}
+
+struct Cat(&'static str);
+
+impl Show for Cat {
+    fn len(&self) -> usize { cats::Format::len(&NAME, &self.0) }
+    fn write<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<usize> {
+        cats::Format::write(&NAME, &self.0, w)
+    }
+}
+
+impl_display_via_show!(Cat);
+
+#[test]
+fn display_via_show_returns_fmt_error() {
+    let mut s = String::new();
+
+    assert!(write!(s, "{}", Cat("Tom")).is_ok());
+    assert!(write!(s, "{}", Cat("Mittens")).is_err());
+    assert_eq!(s, "Tom ");
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn async_cats_resolve_to_the_overflow() {
+    let rt      = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let mut out = Vec::new();
+
+    let err = rt.block_on(afcat!(out, NAME; "Mittens")).unwrap_err();
+
+    assert_eq!(overflow_of(&ScatError::Write(err)), Some(OVERFLOW));
+    assert!(out.is_empty());
+}
@@ -0,0 +1,80 @@
+// Copyright (c) 2015, Sam Payson
+// 
+// Permission is hereby granted, free of charge, to any person obtaining a copy of this software and
+// associated documentation files (the "Software"), to deal in the Software without restriction,
+// including without limitation the rights to use, copy, modify, merge, publish, distribute,
+// sublicense, and/or sell copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+// 
+// The above copyright notice and this permission notice shall be included in all copies or
+// substantial portions of the Software.
+// 
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR IMPLIED, INCLUDING BUT
+// NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM,
+// DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+//! `cats::printf` checked against glibc's printf, over a table of formats, arguments and what
+//! glibc printed for them. `printf/glibc.py` generates the table, and says what it leaves out.
+
+extern crate cats;
+
+use cats::printf::{self, Arg};
+
+const TABLE: &str = include_str!("printf/glibc.tsv");
+
+fn arg<'a>(kind: &str, value: &'a str) -> Arg<'a> {
+    match kind {
+        "i" => Arg::Int(value.parse().unwrap()),
+        "f" => Arg::Float(value.parse().unwrap()),
+        "s" => Arg::Str(value),
+        "c" => Arg::Char(value.chars().next().unwrap()),
+        _   => panic!("unknown argument kind {:?}", kind),
+    }
+}
+
+#[test]
+fn matches_glibc() {
+    let mut rows       = 0;
+    let mut mismatches = Vec::new();
+
+    for line in TABLE.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+
+        assert_eq!(fields.len(), 4, "malformed row {:?}", line);
+
+        let (fmt, kind, value, expected) = (fields[0], fields[1], fields[2], fields[3]);
+
+        match printf::format(fmt, &[arg(kind, value)]) {
+            Ok(ref s) if s == expected => {},
+            got                        => mismatches.push((fmt, value, expected, got)),
+        }
+
+        rows += 1;
+    }
+
+    assert!(rows > 8000, "only {} rows in the table", rows);
+
+    // Report a handful, since one bug tends to show up in many rows.
+    assert!(mismatches.is_empty(), "{} of {} rows differ from glibc, including {:#?}",
+            mismatches.len(), rows, &mismatches[..mismatches.len().min(10)]);
+}
+
+#[test]
+fn lengths_only_wrap_negatives_shown_as_unsigned() {
+    assert_eq!(printf::format("%hhd %hx", &[Arg::Int(300), Arg::Int(0x1ffff)]).unwrap(),
+               "300 1ffff");
+    assert_eq!(printf::format("%hhx %hu", &[Arg::Int(-1), Arg::Int(-1)]).unwrap(), "ff 65535");
+}
+
+#[test]
+fn rejects_precisions_too_large_to_show() {
+    assert_eq!(printf::format("%.65535f", &[Arg::Float(1.0)]).map(|s| s.len()), Ok(65537));
+    assert_eq!(printf::format("%.70000f", &[Arg::Float(1.0)]), Err(printf::Error::BadSpec(0)));
+    assert_eq!(printf::format("%.70000s", &[Arg::Str("Tom")]), Err(printf::Error::BadSpec(0)));
+
+    assert_eq!(printf::format("%-5.*e|", &[Arg::Uint(70000), Arg::Float(1.0)]),
+               Err(printf::Error::BadPrecision(0)));
+    assert_eq!(printf::format("%*.*g", &[Arg::Int(3), Arg::Int(1 << 40), Arg::Float(1.0)]),
+               Err(printf::Error::BadPrecision(1)));
+}
@@ -0,0 +1,79 @@
+#!/usr/bin/env python3
+# Regenerates glibc.tsv, the table tests/printf.rs checks `cats::printf` against, by running each
+# format and value through glibc's snprintf:
+#
+#     python3 tests/printf/glibc.py > tests/printf/glibc.tsv
+#
+# Each row is a format, the kind of argument ('i', 'f', 's' or 'c'), the argument, and what glibc
+# printed, separated by tabs. Strings are all ASCII, and an integer given 'h' or 'hh' is in range
+# of the narrower type, since those are two of the ways the `cats::printf` docs say it differs
+# from C. Without a length, an integer is passed as an `int`.
+
+import ctypes
+import itertools
+
+libc = ctypes.CDLL("libc.so.6")
+buf  = ctypes.create_string_buffer(512)
+
+
+def snprintf(fmt, arg):
+    n = libc.snprintf(buf, len(buf), fmt.encode(), arg)
+    assert 0 <= n < len(buf)
+    return buf.raw[:n].decode()
+
+
+# glibc 2.36 drops the zeros that '#' should keep when rounding carries a '%#g' into exponent
+# form, printing "1.e+06" for 999999.5 where C asks for "1.00000e+06", as `cats::printf` does.
+GLIBC_BUGS = {("%-#12g", "999999.5"), ("%-#12G", "999999.5")}
+
+
+def row(fmt, kind, value, arg):
+    if (fmt, str(value)) in GLIBC_BUGS:
+        return
+
+    out = snprintf(fmt, arg)
+    assert "\t" not in out and "\n" not in out
+    print("\t".join([fmt, kind, str(value), out]))
+
+
+FLAGS  = ["".join(f for f, on in zip("-+ #0", bits) if on)
+          for bits in itertools.product([False, True], repeat=5)]
+WIDTHS = ["", "7"]
+PRECS  = ["", ".0", ".3"]
+
+INTS   = [0, -1, 42, -2147483648]
+FLOATS = [-0.0, 2.5, 1234.5678, 1e-05]
+STRS   = ["", "Tom"]
+
+# Every combination of flags, with and without a width and a precision.
+for flags, width, prec in itertools.product(FLAGS, WIDTHS, PRECS):
+    spec = "%" + flags + width + prec
+
+    for conv in "duoxX":
+        for v in INTS:
+            row(spec + conv, "i", v, ctypes.c_int(v))
+
+    for conv in "feEgG":
+        for v in FLOATS:
+            row(spec + conv, "f", repr(v), ctypes.c_double(v))
+
+    for v in STRS:
+        row(spec + "s", "s", v, v.encode())
+
+    if not prec:
+        row(spec + "c", "c", "A", ctypes.c_int(ord("A")))
+
+# Lengths, which set the width a negative number is wrapped to when it's shown as unsigned.
+for length, values in [("hh", [-1, -128, 127]), ("h", [-1, -300, 32767]),
+                       ("l", [-1, 1 << 40]), ("ll", [-(1 << 63), (1 << 63) - 1]),
+                       ("j", [-1]), ("z", [-1]), ("t", [-1])]:
+    for conv in "diuoxX":
+        for v in values:
+            row("%" + length + conv, "i", v, ctypes.c_longlong(v))
+
+# Floats at the edges, with a few specs each.
+for spec in ["%", "%+12.3", "%-#12", "%012.0", "%.17"]:
+    for conv in "fFeEgG":
+        for v in [0.1, 0.5, 1.5, 999999.5, 1e21, -1e-300, 5e-324, 1.7976931348623157e308,
+                  float("inf"), float("-inf"), float("nan")]:
+            row(spec + conv, "f", repr(v), ctypes.c_double(v))